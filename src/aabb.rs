@@ -0,0 +1,86 @@
+use glam::{Affine3A, BVec3A, Vec3A};
+
+use crate::{ray::Ray, Vertex};
+
+// axis aligned bounding box, used to skip objects a ray cannot hit
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vertex,
+    pub max: Vertex,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        // an empty box that anything can be added to
+        Self {
+            min: Vertex::splat(f32::INFINITY),
+            max: Vertex::splat(f32::NEG_INFINITY),
+        }
+    }
+}
+
+impl Aabb {
+    pub fn new(min: Vertex, max: Vertex) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Vertex>) -> Self {
+        points
+            .into_iter()
+            .fold(Self::default(), |b, &p| b.union(&Self::new(p, p)))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self::new(self.min.max(other.min), self.max.min(other.max))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    pub fn transformed(&self, t: Affine3A) -> Self {
+        // the box around all eight transformed corners
+        let corners = (0..8)
+            .map(|i| {
+                let pick = BVec3A::new(i & 1 != 0, i & 2 != 0, i & 4 != 0);
+                t.transform_point3a(Vertex::select(pick, self.max, self.min))
+            })
+            .collect::<Vec<_>>();
+        Self::from_points(&corners)
+    }
+
+    pub fn pad(&self, amount: f32) -> Self {
+        Self::new(self.min - amount, self.max + amount)
+    }
+
+    pub fn centroid(&self) -> Vertex {
+        (self.min + self.max) / 2.
+    }
+
+    pub fn size(&self) -> Vec3A {
+        self.max - self.min
+    }
+
+    pub fn hit_by(&self, ray: &Ray) -> Option<(f32, f32)> {
+        // slab test, giving the range of t the ray spends inside the box
+        if self.is_empty() {
+            return None;
+        }
+        let (mut near, mut far) = (f32::NEG_INFINITY, f32::INFINITY);
+        for axis in 0..3 {
+            let inv = 1. / ray.direction[axis];
+            let t0 = (self.min[axis] - ray.position[axis]) * inv;
+            let t1 = (self.max[axis] - ray.position[axis]) * inv;
+            if t0.is_nan() || t1.is_nan() {
+                continue; // ray lies exactly on a slab boundary
+            }
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+        (near <= far).then_some((near, far))
+    }
+}
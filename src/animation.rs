@@ -0,0 +1,231 @@
+use glam::{Affine3A, Quat, Vec3, Vec3A};
+
+use crate::{colour::Colour, scene::Scene, Vertex};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    // straight lines between keys
+    Linear,
+    // a smooth catmull-rom curve through the keys
+    Cubic,
+}
+
+// values that can be blended between keys
+pub trait Animatable: Copy + PartialEq {
+    // a weighted sum of values, with weights adding up to one
+    fn combine(terms: &[(f32, Self)]) -> Self;
+}
+
+impl Animatable for f32 {
+    fn combine(terms: &[(f32, Self)]) -> Self {
+        terms.iter().map(|(w, v)| w * v).sum()
+    }
+}
+
+impl Animatable for Vec3A {
+    fn combine(terms: &[(f32, Self)]) -> Self {
+        terms.iter().fold(Vec3A::ZERO, |sum, &(w, v)| sum + w * v)
+    }
+}
+
+impl Animatable for Colour {
+    fn combine(terms: &[(f32, Self)]) -> Self {
+        terms
+            .iter()
+            .fold(Colour::from_rgba(0., 0., 0., 0.), |sum, &(w, c)| {
+                Colour::from_rgba(
+                    sum.r + w * c.r,
+                    sum.g + w * c.g,
+                    sum.b + w * c.b,
+                    sum.a + w * c.a,
+                )
+            })
+    }
+}
+
+impl Animatable for Affine3A {
+    fn combine(terms: &[(f32, Self)]) -> Self {
+        // blend the scale, rotation and translation separately so objects don't shear
+        let mut scale = Vec3::ZERO;
+        let mut translation = Vec3::ZERO;
+        let mut rotation = QuatSum::default();
+        for &(w, t) in terms {
+            let (s, r, p) = t.to_scale_rotation_translation();
+            scale += w * s;
+            translation += w * p;
+            rotation.add(w, r);
+        }
+        Affine3A::from_scale_rotation_translation(scale, rotation.normalize(), translation)
+    }
+}
+
+// a running weighted sum of quaternions kept in the same hemisphere, so they blend the short
+// way round
+#[derive(Default)]
+struct QuatSum {
+    sum: Option<Quat>,
+}
+
+impl QuatSum {
+    fn add(&mut self, weight: f32, q: Quat) {
+        self.sum = Some(match self.sum {
+            None => q * weight,
+            Some(sum) if sum.dot(q) < 0. => sum - q * weight,
+            Some(sum) => sum + q * weight,
+        });
+    }
+
+    fn normalize(&self) -> Quat {
+        self.sum.map_or(Quat::IDENTITY, Quat::normalize)
+    }
+}
+
+// a value changing over time, given by keys it passes through
+#[derive(Clone, Debug)]
+pub struct Track<T> {
+    keys: Vec<(f32, T)>,
+    interpolation: Interpolation,
+}
+
+impl<T: Animatable> Track<T> {
+    pub fn new(interpolation: Interpolation) -> Self {
+        Self {
+            keys: Vec::new(),
+            interpolation,
+        }
+    }
+
+    // a track that holds one value forever
+    pub fn constant(value: T) -> Self {
+        let mut track = Self::new(Interpolation::Linear);
+        track.add_key(0., value);
+        track
+    }
+
+    pub fn add_key(&mut self, time: f32, value: T) {
+        // keys are kept in time order, with a later key at the same time replacing the earlier
+        let i = self.keys.partition_point(|k| k.0 < time);
+        if self.keys.get(i).is_some_and(|k| k.0 == time) {
+            self.keys[i].1 = value;
+        } else {
+            self.keys.insert(i, (time, value));
+        }
+    }
+
+    // the value at a time, or none if the track has no keys yet
+    pub fn at(&self, time: f32) -> Option<T> {
+        let keys = &self.keys;
+        let last = keys.len().checked_sub(1)?;
+
+        // held at the first and last keys outside of their range
+        let i = keys.partition_point(|k| k.0 <= time);
+        if i == 0 {
+            return Some(keys[0].1);
+        }
+        if i > last {
+            return Some(keys[last].1);
+        }
+
+        let ((t0, p0), (t1, p1)) = (keys[i - 1], keys[i]);
+        let s = (time - t0) / (t1 - t0);
+        Some(match self.interpolation {
+            Interpolation::Linear => T::combine(&[(1. - s, p0), (s, p1)]),
+            Interpolation::Cubic => {
+                // hermite curve with tangents from the neighbouring keys, one sided at the ends
+                let (tb, pb) = keys[i.saturating_sub(2)];
+                let (ta, pa) = keys[(i + 1).min(last)];
+                let (s2, s3) = (s * s, s * s * s);
+                let h00 = 2. * s3 - 3. * s2 + 1.;
+                let h10 = s3 - 2. * s2 + s;
+                let h01 = -2. * s3 + 3. * s2;
+                let h11 = s3 - s2;
+                let a = h10 * (t1 - t0) / (t1 - tb);
+                let b = h11 * (t1 - t0) / (ta - t0);
+                T::combine(&[(h00 - b, p0), (h01 + a, p1), (-a, pb), (b, pa)])
+            }
+        })
+    }
+}
+
+// keyframe tracks for the camera and for objects and lights in a scene, found by their
+// position in the scene's lists
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub camera_position: Track<Vertex>,
+    pub camera_look: Track<Vertex>,
+    pub camera_up: Track<Vec3A>,
+    objects: Vec<(usize, Track<Affine3A>)>,
+    light_positions: Vec<(usize, Track<Vertex>)>,
+    light_intensities: Vec<(usize, Track<Colour>)>,
+}
+
+impl Animation {
+    pub fn new(
+        camera_position: Track<Vertex>,
+        camera_look: Track<Vertex>,
+        camera_up: Track<Vec3A>,
+    ) -> Self {
+        Self {
+            camera_position,
+            camera_look,
+            camera_up,
+            objects: Vec::new(),
+            light_positions: Vec::new(),
+            light_intensities: Vec::new(),
+        }
+    }
+
+    pub fn animate_object(&mut self, index: usize, transform: Track<Affine3A>) {
+        self.objects.push((index, transform));
+    }
+
+    pub fn animate_light_position(&mut self, index: usize, position: Track<Vertex>) {
+        self.light_positions.push((index, position));
+    }
+
+    pub fn animate_light_intensity(&mut self, index: usize, intensity: Track<Colour>) {
+        self.light_intensities.push((index, intensity));
+    }
+
+    // the camera's position, look point and up direction, or none if a track has no keys
+    pub fn camera_at(&self, time: f32) -> Option<(Vertex, Vertex, Vec3A)> {
+        Some((
+            self.camera_position.at(time)?,
+            self.camera_look.at(time)?,
+            self.camera_up.at(time)?,
+        ))
+    }
+
+    pub fn apply(&self, scene: &mut Scene, time: f32) {
+        // move objects from where the scene was built, so apply this to a freshly built scene
+        // tracks without keys leave their object or light as it was built
+        for (index, track) in &self.objects {
+            if let Some(transform) = track.at(time) {
+                scene.object_list[*index].apply_transform(transform);
+            }
+        }
+        for (index, track) in &self.light_positions {
+            if let Some(position) = track.at(time) {
+                scene.light_list[*index].set_position(position);
+            }
+        }
+        for (index, track) in &self.light_intensities {
+            if let Some(intensity) = track.at(time) {
+                scene.light_list[*index].set_intensity(intensity);
+            }
+        }
+    }
+
+    pub fn scene_changes(&self, from: f32, to: f32) -> bool {
+        // when only the camera moves the scene, and anything built from it, can be reused
+        self.objects.iter().any(|(_, t)| t.at(from) != t.at(to))
+            || self
+                .light_positions
+                .iter()
+                .any(|(_, t)| t.at(from) != t.at(to))
+            || self
+                .light_intensities
+                .iter()
+                .any(|(_, t)| t.at(from) != t.at(to))
+    }
+}
@@ -0,0 +1,79 @@
+use glam::Vec3A;
+use rand::Rng;
+
+use super::{Camera, Shutter};
+use crate::{ray::Ray, Vertex};
+
+// parallel rays with no perspective, so sizes don't shrink with distance
+#[derive(Clone, Debug, PartialEq)]
+pub struct Orthographic {
+    pub width: usize,
+    pub height: usize,
+    pub position: Vertex,
+    pub w: Vec3A,
+    pub v: Vec3A,
+    pub u: Vec3A,
+    bottom_left: Vertex,
+    view_width: f32,
+    view_height: f32,
+    samples: usize,
+    shutter: Shutter,
+}
+
+impl Orthographic {
+    // the view is centred on the position and view_width across, in scene units
+    pub fn new(
+        position: Vertex,
+        look: Vertex,
+        up: Vec3A,
+        view_width: f32,
+        width: usize,
+        height: usize,
+        samples: usize,
+    ) -> Self {
+        let w = (look - position).normalize();
+        let u = (up.cross(w)).normalize();
+        let v = w.cross(u);
+
+        // square pixels, so the height follows the image's aspect ratio
+        let view_height = view_width * height as f32 / width as f32;
+        let bottom_left = position - 0.5 * (view_width * u + view_height * v);
+
+        Self {
+            width,
+            height,
+            position,
+            w,
+            v,
+            u,
+            bottom_left,
+            view_width,
+            view_height,
+            samples,
+            shutter: Shutter::default(),
+        }
+    }
+
+    pub fn with_shutter(self, open: f32, close: f32) -> Self {
+        Self {
+            shutter: Shutter::new(open, close),
+            ..self
+        }
+    }
+}
+
+impl Camera for Orthographic {
+    fn get_ray_pixel(&self, x: usize, y: usize) -> Ray {
+        // every ray leaves the image plane in the viewing direction, from a random point
+        // within the pixel
+        let mut rng = rand::thread_rng();
+        let across = (x as f32 + rng.gen::<f32>()) / self.width as f32 * self.view_width;
+        let up = (y as f32 + rng.gen::<f32>()) / self.height as f32 * self.view_height;
+        Ray::new(self.bottom_left + across * self.u + up * self.v, self.w)
+            .with_time(self.shutter.sample())
+    }
+
+    fn samples(&self) -> usize {
+        self.samples
+    }
+}
@@ -0,0 +1,104 @@
+use glam::Vec3A;
+use rand::Rng;
+
+use super::{Camera, Shutter};
+use crate::{ray::Ray, Vertex};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FullCamera {
+    pub width: usize,
+    pub height: usize,
+    pub fov: f32,
+    pub position: Vertex,
+    pub w: Vec3A,
+    pub v: Vec3A,
+    pub u: Vec3A,
+    bottom_left_pixel: Vec3A,
+    samples: usize,
+    lens_radius: f32,
+    focal_distance: f32,
+    shutter: Shutter,
+}
+
+impl FullCamera {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        fov: f32,
+        position: Vertex,
+        look: Vertex,
+        up: Vec3A,
+        width: usize,
+        height: usize,
+        samples: usize,
+        aperture: f32,
+    ) -> Self {
+        let w = (look - position).normalize();
+        let u = (up.cross(w)).normalize();
+        let v = w.cross(u);
+
+        let focal_distance = (look - position).length();
+        let horizontal = 0.5 * u;
+        let vertical = 0.5 * v;
+
+        let bottom_left_pixel = position + (-horizontal - vertical + fov * w) * focal_distance;
+        let lens_radius = aperture / 2.; // used to create sample rays
+
+        Self {
+            width,
+            height,
+            fov,
+            position,
+            w,
+            v,
+            u,
+            bottom_left_pixel,
+            samples,
+            lens_radius,
+            focal_distance,
+            shutter: Shutter::default(),
+        }
+    }
+
+    pub fn with_shutter(self, open: f32, close: f32) -> Self {
+        Self {
+            shutter: Shutter::new(open, close),
+            ..self
+        }
+    }
+}
+
+impl Camera for FullCamera {
+    fn get_ray_pixel(&self, x: usize, y: usize) -> Ray {
+        // convert pixel coordinates to world coordinates
+        // add a small amount of randomness
+        let mut rng = rand::thread_rng();
+        // offset simulates thin lens model
+        let offset = random_in_unit_disc() * self.lens_radius;
+        Ray::new(
+            self.position + offset,
+            (self.bottom_left_pixel
+                + (x as f32 + rng.gen::<f32>()) / self.width as f32 * self.u * self.focal_distance
+                + (y as f32 + rng.gen::<f32>()) / self.height as f32
+                    * self.v
+                    * self.focal_distance
+                - self.position
+                - offset)
+                .normalize(),
+        )
+        .with_time(self.shutter.sample())
+    }
+
+    fn samples(&self) -> usize {
+        self.samples
+    }
+}
+
+pub fn random_in_unit_disc() -> Vec3A {
+    let mut rng = rand::thread_rng();
+    loop {
+        let p = Vec3A::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 0.);
+        if p.length_squared() < 1. {
+            return p;
+        }
+    }
+}
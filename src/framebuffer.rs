@@ -16,6 +16,12 @@ pub struct FrameBuffer {
     height: usize,
 }
 
+impl Default for FrameBuffer {
+    fn default() -> Self {
+        Self::new(1024, 1024)
+    }
+}
+
 impl FrameBuffer {
     pub fn new(width: usize, height: usize) -> Self {
         FrameBuffer {
@@ -24,9 +30,6 @@ impl FrameBuffer {
             height,
         }
     }
-    pub fn default() -> Self {
-        Self::new(1024, 1024)
-    }
 
     pub fn plot_pixel(&mut self, x: usize, y: usize, red: f32, green: f32, blue: f32) {
         self.buf[y * self.width + x].colour.r = red;
@@ -64,7 +67,7 @@ impl FrameBuffer {
     pub fn write_rgb_png(&self, filename: &str) -> io::Result<()> {
         // Open file
         let file = File::create(filename)?;
-        let ref mut w = BufWriter::new(file);
+        let w = &mut BufWriter::new(file);
 
         let mut encoder = Encoder::new(w, self.width as u32, self.height as u32);
         encoder.set_color(ColorType::Rgba);
@@ -86,7 +89,7 @@ impl FrameBuffer {
     pub fn write_depth_png(&self, filename: &str) -> io::Result<()> {
         // Open file
         let file = File::create(filename)?;
-        let ref mut w = BufWriter::new(file);
+        let w = &mut BufWriter::new(file);
 
         let mut encoder = Encoder::new(w, self.width as u32, self.height as u32);
         encoder.set_color(ColorType::Rgba);
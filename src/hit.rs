@@ -1,6 +1,6 @@
-use glam::Vec3A;
+use glam::{Vec2, Vec3A};
 
-use crate::{material::Material, object::Object, ray::Ray, Vertex};
+use crate::{colour::Colour, material::Material, object::Object, ray::Ray, Vertex};
 
 #[derive(Clone, Debug)]
 pub struct Hit<'obj> {
@@ -10,5 +10,19 @@ pub struct Hit<'obj> {
     pub material: &'obj (dyn Material + Send + Sync),
     pub position: Vertex,
     pub normal: Vec3A,
+    pub tangent: Vec3A,
+    pub uv: Vec2,
+    pub colour: Option<Colour>,
     pub incident: Ray,
 }
+
+pub fn tangent_around_y(normal: Vec3A) -> Vec3A {
+    // tangent circling the y axis, for surfaces without a natural parameterisation
+    let tangent = Vec3A::Y.cross(normal);
+    if tangent.length_squared() < 0.000001 {
+        // the normal is parallel to the axis so any perpendicular direction will do
+        normal.any_orthonormal_vector()
+    } else {
+        tangent.normalize()
+    }
+}
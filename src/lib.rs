@@ -1,16 +1,22 @@
 use glam::Vec3A;
 
+pub mod aabb;
+pub mod animation;
+pub mod camera;
 pub mod colour;
 pub mod framebuffer;
-pub mod fullcamera;
 pub mod hit;
 pub mod light;
 pub mod linedrawer;
 pub mod material;
+pub mod medium;
 pub mod object;
 pub mod photonmap;
+pub mod polynomial;
 pub mod ray;
 pub mod scene;
+pub mod texture;
+pub mod transform;
 
 // type alias for Vertex
 pub type Vertex = Vec3A;
@@ -17,6 +17,12 @@ pub trait Light: Debug {
     // get light intensity at given location
     fn get_intensity(&self, surface: Vertex) -> Colour;
 
+    // move the light, ignored by lights without a position
+    fn set_position(&mut self, _position: Vertex) {}
+
+    // change the brightness and colour of the light, ignored by lights that can't be dimmed
+    fn set_intensity(&mut self, _intensity: Colour) {}
+
     // emit a new photon
     fn generate_photon(&self) -> Photon;
 }
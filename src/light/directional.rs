@@ -3,21 +3,12 @@ use glam::Vec3A;
 use super::Light;
 use crate::{colour::Colour, photonmap::Photon, Vertex};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Directional {
     pub direction: Vec3A,
     pub intensity: Colour,
 }
 
-impl Default for Directional {
-    fn default() -> Self {
-        Self {
-            direction: Vec3A::default(),
-            intensity: Colour::default(),
-        }
-    }
-}
-
 impl Directional {
     pub fn new(direction: Vec3A, intensity: Colour) -> Self {
         Self {
@@ -41,6 +32,10 @@ impl Light for Directional {
         self.intensity
     }
 
+    fn set_intensity(&mut self, intensity: Colour) {
+        self.intensity = intensity;
+    }
+
     fn generate_photon(&self) -> Photon {
         // cannot generate a photon an infinite distance away
         unimplemented!()
@@ -9,21 +9,12 @@ use crate::{
     Vertex,
 };
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Point {
     pub position: Vertex,
     pub intensity: Colour,
 }
 
-impl Default for Point {
-    fn default() -> Self {
-        Self {
-            position: Vertex::default(),
-            intensity: Colour::default(),
-        }
-    }
-}
-
 impl Point {
     pub fn new(position: Vertex, intensity: Colour) -> Self {
         Self {
@@ -47,6 +38,14 @@ impl Light for Point {
         self.intensity
     }
 
+    fn set_position(&mut self, position: Vertex) {
+        self.position = position;
+    }
+
+    fn set_intensity(&mut self, intensity: Colour) {
+        self.intensity = intensity;
+    }
+
     fn generate_photon(&self) -> Photon {
         let direction = random_in_unit_sphere();
         Photon {
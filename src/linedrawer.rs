@@ -5,7 +5,7 @@ use crate::framebuffer::FrameBuffer;
 pub fn draw_line(f: &mut FrameBuffer, sx: i32, sy: i32, ex: i32, ey: i32) {
     if (sx == ex) && (sy == ey) {
         f.plot_pixel(sx as usize, sy as usize, 1., 1., 1.);
-    } else if ((ex - sx) as i32).abs() >= ((ey - sy) as i32).abs() {
+    } else if (ex - sx).abs() >= (ey - sy).abs() {
         draw_x_line(f, sx, sy, ex, ey);
     } else {
         draw_y_line(f, sx, sy, ex, ey);
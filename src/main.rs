@@ -1,13 +1,14 @@
 use clap::Parser;
 use glam::{Affine3A, Vec3, Vec3A};
 use raytracer::{
+    animation::{Animation, Interpolation, Track},
+    camera::{orthographic::Orthographic, perspective::FullCamera, Camera},
     colour::Colour,
     framebuffer::FrameBuffer,
-    fullcamera::FullCamera,
     light::point::Point,
     material::{dielectric::Dielectric, diffuse::Diffuse, metallic::Metallic, phong::Phong},
     object::{
-        csg::{Csg, Op},
+        csg::{Csg, CsgBuilder, Op},
         polymesh::PolyMesh,
         sphere::Sphere,
         triangle::Triangle,
@@ -38,6 +39,26 @@ struct Args {
     // samples
     #[arg(long, default_value_t = 500)]
     samples: usize,
+
+    // last frame of an animation to render to numbered pngs, a single still is rendered if unset
+    #[arg(long)]
+    end_frame: Option<usize>,
+
+    // first frame of the animation
+    #[arg(long, default_value_t = 0)]
+    start_frame: usize,
+
+    // frames per second, converting frame numbers to keyframe times
+    #[arg(long, default_value_t = 24.)]
+    fps: f32,
+
+    // fraction of each frame the shutter stays open for, blurring moving objects
+    #[arg(long, default_value_t = 0.)]
+    shutter: f32,
+
+    // width of the view in scene units, switching to an orthographic camera
+    #[arg(long)]
+    view_width: Option<f32>,
 }
 
 fn main() {
@@ -54,28 +75,22 @@ fn main() {
         }
     }
 
+    let animation = build_animation();
+    if let Some(end_frame) = args.end_frame {
+        render_frames(&args, &scene_option, &animation, end_frame);
+        return;
+    }
+
     // create framebuffer for render output
     let mut fb = FrameBuffer::new(args.resolution, args.resolution);
 
     // create and setup the scene
     let mut scene = Scene::default();
-    match scene_option {
-        SceneOption::Cornell => build_c_box(&mut scene),
-        SceneOption::Full => build_final_scene(&mut scene),
-        SceneOption::Material => build_material_scene(&mut scene),
-    }
+    build_scene(&scene_option, &mut scene);
+    animation.apply(&mut scene, 0.);
 
     // define a camera
-    let camera = FullCamera::new(
-        1.,
-        Vertex::new(0., 0., 0.),
-        Vertex::new(0., 0., 8.),
-        Vec3A::new(0., 1., 0.),
-        fb.width(),
-        fb.height(),
-        args.samples,
-        0.,
-    );
+    let camera = build_camera(&args, &animation, 0.);
 
     // build caustics map WARNING: SLOW
     #[cfg(feature = "unfinished")]
@@ -109,6 +124,73 @@ fn main() {
     }
 }
 
+fn build_scene(scene_option: &SceneOption, scene: &mut Scene) {
+    match scene_option {
+        SceneOption::Cornell => build_c_box(scene),
+        SceneOption::Full => build_final_scene(scene),
+        SceneOption::Material => build_material_scene(scene),
+    }
+}
+
+fn build_animation() -> Animation {
+    // the camera swings to the right and back over four seconds, looking at the middle
+    let mut position = Track::new(Interpolation::Cubic);
+    position.add_key(0., Vertex::new(0., 0., 0.));
+    position.add_key(2., Vertex::new(1.5, 0.5, 0.5));
+    position.add_key(4., Vertex::new(0., 0., 0.));
+    Animation::new(
+        position,
+        Track::constant(Vertex::new(0., 0., 8.)),
+        Track::constant(Vec3A::new(0., 1., 0.)),
+    )
+}
+
+fn build_camera(args: &Args, animation: &Animation, time: f32) -> Box<dyn Camera> {
+    // stills open the shutter for the same fraction of a frame as animations do
+    let shutter = args.shutter / args.fps;
+    let (position, look, up) = animation
+        .camera_at(time)
+        .expect("camera tracks should have keys");
+    let (width, height) = (args.resolution, args.resolution);
+    match args.view_width {
+        Some(view_width) => Box::new(
+            Orthographic::new(position, look, up, view_width, width, height, args.samples)
+                .with_shutter(time, time + shutter),
+        ),
+        None => Box::new(
+            FullCamera::new(1., position, look, up, width, height, args.samples, 0.)
+                .with_shutter(time, time + shutter),
+        ),
+    }
+}
+
+fn render_frames(args: &Args, scene_option: &SceneOption, animation: &Animation, end_frame: usize) {
+    let time = |frame: usize| frame as f32 / args.fps;
+    let mut frame = args.start_frame;
+    while frame <= end_frame {
+        // the scene and its photon map are only rebuilt once an object or light has moved
+        let built = time(frame);
+        let mut scene = Scene::default();
+        build_scene(scene_option, &mut scene);
+        animation.apply(&mut scene, built);
+        let pmap = PhotonMap::build(&scene);
+
+        loop {
+            let camera = build_camera(args, animation, time(frame));
+
+            let mut fb = FrameBuffer::new(args.resolution, args.resolution);
+            camera.render(&scene, &mut fb, &pmap);
+            fb.write_rgb_png(&format!("frame_{frame:04}.png"))
+                .expect("failed to write RGB output to PNG file");
+
+            frame += 1;
+            if frame > end_frame || animation.scene_changes(built, time(frame)) {
+                break;
+            }
+        }
+    }
+}
+
 fn build_final_scene(scene: &mut Scene) {
     // create materials
     let mat_white = Diffuse::new(Colour::from_rgb(0.6, 0.6, 0.6));
@@ -122,16 +204,16 @@ fn build_final_scene(scene: &mut Scene) {
     scene.add_object(Triangle::new(
         [
             Vec3A::new(-3., -3., 10.),
-            Vec3A::new(-3., -3., 4.),
             Vec3A::new(3., -3., 4.),
+            Vec3A::new(-3., -3., 4.),
         ],
         mat_metal_r,
     ));
     scene.add_object(Triangle::new(
         [
             Vec3A::new(3., -3., 4.),
-            Vec3A::new(3., -3., 10.),
             Vec3A::new(-3., -3., 10.),
+            Vec3A::new(3., -3., 10.),
         ],
         mat_metal_r,
     ));
@@ -140,16 +222,16 @@ fn build_final_scene(scene: &mut Scene) {
     scene.add_object(Triangle::new(
         [
             Vec3A::new(-3., 3., 4.),
-            Vec3A::new(-3., 3., 10.),
             Vec3A::new(3., 3., 10.),
+            Vec3A::new(-3., 3., 10.),
         ],
         mat_white,
     ));
     scene.add_object(Triangle::new(
         [
             Vec3A::new(3., 3., 10.),
-            Vec3A::new(3., 3., 4.),
             Vec3A::new(-3., 3., 4.),
+            Vec3A::new(3., 3., 4.),
         ],
         mat_white,
     ));
@@ -284,9 +366,10 @@ fn build_final_scene(scene: &mut Scene) {
     let sphere_sm_3 = Sphere::new(Vec3A::new(0.5, -2.75, 6.75), 0.25, mat_glass);
     let sphere_sm_4 = Sphere::new(Vec3A::new(0.75, -2.75, 6.75), 0.25, mat_glass);
 
-    let glass_obj_p1 = Csg::new_branch(sphere_sm_1, sphere_sm_2, Op::Union);
-    let glass_obj_p2 = Csg::new_branch(sphere_sm_3, sphere_sm_4, Op::Union);
-    let mut glass_obj = Csg::new_branch(glass_obj_p1, glass_obj_p2, Op::Union);
+    let mut glass_obj = sphere_sm_1
+        .union(sphere_sm_2)
+        .union(sphere_sm_3)
+        .union(sphere_sm_4);
 
     let t = Affine3A::from_translation(Vec3::new(-0.5, 0., 0.3)) * Affine3A::from_rotation_y(0.05);
     glass_obj.apply_transform(t);
@@ -315,17 +398,12 @@ fn build_final_scene(scene: &mut Scene) {
 
 fn spawn_pyramid(a: Vec3A, b: Vec3A, c: Vec3A, d: Vec3A, e: Vec3A, t: Affine3A) -> Csg {
     let mat_blue = Diffuse::new(Colour::from_rgb(0.25, 0.25, 0.85));
-    let part1 = Csg::new_branch(
+    let sides = [[b, c, e], [c, d, e], [d, a, e]];
+    let mut pyramid = Csg::new_nary(
         Triangle::new([a, b, e], mat_blue),
-        Triangle::new([b, c, e], mat_blue),
+        sides.map(|corners| Triangle::new(corners, mat_blue)),
         Op::Union,
     );
-    let part2 = Csg::new_branch(
-        Triangle::new([c, d, e], mat_blue),
-        Triangle::new([d, a, e], mat_blue),
-        Op::Union,
-    );
-    let mut pyramid = Csg::new_branch(part1, part2, Op::Union);
     pyramid.apply_transform(t);
     pyramid
 }
@@ -360,16 +438,16 @@ fn build_c_box(scene: &mut Scene) {
     scene.add_object(Triangle::new(
         [
             Vec3A::new(-3., -3., 10.),
-            Vec3A::new(-3., -3., 4.),
             Vec3A::new(3., -3., 4.),
+            Vec3A::new(-3., -3., 4.),
         ],
         mat_white,
     ));
     scene.add_object(Triangle::new(
         [
             Vec3A::new(3., -3., 4.),
-            Vec3A::new(3., -3., 10.),
             Vec3A::new(-3., -3., 10.),
+            Vec3A::new(3., -3., 10.),
         ],
         mat_white,
     ));
@@ -378,16 +456,16 @@ fn build_c_box(scene: &mut Scene) {
     scene.add_object(Triangle::new(
         [
             Vec3A::new(-3., 3., 4.),
-            Vec3A::new(-3., 3., 10.),
             Vec3A::new(3., 3., 10.),
+            Vec3A::new(-3., 3., 10.),
         ],
         mat_white,
     ));
     scene.add_object(Triangle::new(
         [
             Vec3A::new(3., 3., 10.),
-            Vec3A::new(3., 3., 4.),
             Vec3A::new(-3., 3., 4.),
+            Vec3A::new(3., 3., 4.),
         ],
         mat_white,
     ));
@@ -487,16 +565,16 @@ fn build_material_scene(scene: &mut Scene) {
     scene.add_object(Triangle::new(
         [
             Vec3A::new(-3., -3., 10.),
-            Vec3A::new(-3., -3., 4.),
             Vec3A::new(3., -3., 4.),
+            Vec3A::new(-3., -3., 4.),
         ],
         mat_white,
     ));
     scene.add_object(Triangle::new(
         [
             Vec3A::new(3., -3., 4.),
-            Vec3A::new(3., -3., 10.),
             Vec3A::new(-3., -3., 10.),
+            Vec3A::new(3., -3., 10.),
         ],
         mat_white,
     ));
@@ -505,16 +583,16 @@ fn build_material_scene(scene: &mut Scene) {
     scene.add_object(Triangle::new(
         [
             Vec3A::new(-3., 3., 4.),
-            Vec3A::new(-3., 3., 10.),
             Vec3A::new(3., 3., 10.),
+            Vec3A::new(-3., 3., 10.),
         ],
         mat_white,
     ));
     scene.add_object(Triangle::new(
         [
             Vec3A::new(3., 3., 10.),
-            Vec3A::new(3., 3., 4.),
             Vec3A::new(-3., 3., 4.),
+            Vec3A::new(3., 3., 4.),
         ],
         mat_white,
     ));
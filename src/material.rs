@@ -9,11 +9,19 @@ use crate::{
     scene::Scene,
 };
 
+pub mod anisotropic;
+pub mod conductor;
+pub mod cutout;
 pub mod dielectric;
 pub mod diffuse;
+pub mod hair;
+pub mod henyeygreenstein;
+pub mod measured;
 pub mod metallic;
+pub mod mix;
 pub mod normalshading;
 pub mod phong;
+pub mod subsurface;
 
 pub trait Material: Debug + Send + Sync {
     fn compute(
@@ -25,4 +33,19 @@ pub trait Material: Debug + Send + Sync {
         pmap: &PhotonMap,
     ) -> Colour;
     fn interact(&self, hit: &Hit) -> Interaction;
+
+    // probability that a hit on this material is kept rather than passed through
+    fn opacity(&self, _hit: &Hit) -> f32 {
+        1.
+    }
+
+    // whether a shadow ray carries on through this hit towards the light
+    fn transmits_shadow(&self, _hit: &Hit) -> bool {
+        false
+    }
+
+    // whether hits on this material are scattering events inside a participating medium
+    fn is_medium(&self) -> bool {
+        false
+    }
 }
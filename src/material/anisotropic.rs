@@ -0,0 +1,165 @@
+use std::f32::consts::PI;
+
+use glam::Vec3A;
+use rand::random;
+
+use super::Material;
+use crate::{
+    colour::Colour,
+    hit::Hit,
+    photonmap::{Interaction, PhotonMap},
+    scene::Scene,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Anisotropic {
+    colour: Colour,
+    roughness_u: f32,
+    roughness_v: f32,
+    rotation: f32,
+}
+
+impl Anisotropic {
+    // roughness_u runs along the hit tangent and roughness_v across it, the rotation in
+    // radians turns the direction of the brushing about the normal
+    pub fn new(specular: Colour, roughness_u: f32, roughness_v: f32, rotation: f32) -> Self {
+        Anisotropic {
+            colour: specular,
+            roughness_u: roughness_u.max(0.001),
+            roughness_v: roughness_v.max(0.001),
+            rotation,
+        }
+    }
+
+    fn frame(&self, hit: &Hit) -> (Vec3A, Vec3A, Vec3A) {
+        // orthonormal tangent, bitangent and normal, with the tangent rotated about the normal
+        let n = hit.normal;
+        let t = (hit.tangent - hit.tangent.dot(n) * n)
+            .try_normalize()
+            .unwrap_or_else(|| n.any_orthonormal_vector());
+        let b = n.cross(t);
+        let t = t * self.rotation.cos() + b * self.rotation.sin();
+        (t, n.cross(t), n)
+    }
+
+    fn lambda(&self, w: Vec3A) -> f32 {
+        // Smith masking term for the anisotropic GGX distribution, w in the local frame
+        let a2_tan2 =
+            ((w.x * self.roughness_u).powi(2) + (w.y * self.roughness_v).powi(2)) / w.z.powi(2);
+        (-1. + (1. + a2_tan2).sqrt()) / 2.
+    }
+
+    fn distribution(&self, m: Vec3A) -> f32 {
+        // anisotropic GGX density of microfacet normals, m in the local frame
+        let (au, av) = (self.roughness_u, self.roughness_v);
+        let d = (m.x / au).powi(2) + (m.y / av).powi(2) + m.z.powi(2);
+        1. / (PI * au * av * d * d)
+    }
+
+    fn brdf(&self, wi: Vec3A, wo: Vec3A) -> Colour {
+        // microfacet reflection F * D * G / (4 (wi.n) (wo.n)) between local directions
+        let Some(m) = (wi + wo).try_normalize() else {
+            return Colour::default();
+        };
+        let g = 1. / (1. + self.lambda(wo) + self.lambda(wi));
+        self.fresnel(wi.dot(m)) * (self.distribution(m) * g / (4. * wi.z * wo.z))
+    }
+
+    fn sample_microfacet(&self) -> Vec3A {
+        // importance sample a microfacet normal from the anisotropic GGX distribution
+        let (xi1, xi2) = (random::<f32>(), random::<f32>());
+        let phi = (self.roughness_v * (2. * PI * xi1).sin())
+            .atan2(self.roughness_u * (2. * PI * xi1).cos());
+        let inv_a2 =
+            (phi.cos() / self.roughness_u).powi(2) + (phi.sin() / self.roughness_v).powi(2);
+        let tan2_theta = xi2 / ((1. - xi2) * inv_a2);
+        let cos_theta = 1. / (1. + tan2_theta).sqrt();
+        let sin_theta = (1. - cos_theta.powi(2)).max(0.).sqrt();
+        Vec3A::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+    }
+
+    fn fresnel(&self, cos: f32) -> Colour {
+        // Schlick approximation using the specular colour as reflectance at normal incidence
+        let k = (1. - cos).powi(5);
+        Colour::from_rgb(
+            self.colour.r + (1. - self.colour.r) * k,
+            self.colour.g + (1. - self.colour.g) * k,
+            self.colour.b + (1. - self.colour.b) * k,
+        )
+    }
+}
+
+impl Material for Anisotropic {
+    fn compute(
+        &self,
+        viewer: Vec3A,
+        hit: &Hit,
+        recurse: usize,
+        scene: &Scene,
+        pmap: &PhotonMap,
+    ) -> Colour {
+        if recurse < 1 {
+            return Colour::default();
+        }
+        let (t, b, n) = self.frame(hit);
+        let to_local = |w: Vec3A| Vec3A::new(w.dot(t), w.dot(b), w.dot(n));
+        let wo = to_local(-hit.incident.direction.normalize());
+
+        // the highlights of point and directional lights, which sampled rays never reach
+        let mut colour = Colour::default();
+        for light in &scene.light_list {
+            // ldir is direction towards the light
+            let (ldir, mut lit) = light.get_direction(hit.position);
+            if ldir.dot(hit.normal) < 0. || wo.z <= 0. {
+                // light or viewer is below the surface
+                lit = false;
+            }
+
+            if lit {
+                let limit = match light.get_position() {
+                    None => f32::INFINITY,
+                    Some(pos) => (pos - hit.position).length(),
+                };
+                // check for objects between position and light
+                lit = !scene.shadow_trace(
+                    &hit.incident.spawn(hit.position + 0.0001 * ldir, ldir),
+                    limit,
+                );
+            }
+
+            if lit {
+                let wi = to_local(ldir);
+                colour += light.get_intensity(hit.position) * self.brdf(wi, wo) * wi.z;
+            }
+        }
+
+        // reflections of the rest of the scene from a sampled microfacet
+        if let Interaction::Reflected { ray, attenuation } = self.interact(hit) {
+            colour += attenuation * scene.raytrace(ray, recurse - 1, viewer, pmap).0;
+        }
+        colour
+    }
+
+    fn interact(&self, hit: &Hit) -> Interaction {
+        let (t, b, n) = self.frame(hit);
+        let to_local = |w: Vec3A| Vec3A::new(w.dot(t), w.dot(b), w.dot(n));
+
+        let wo = to_local(-hit.incident.direction.normalize());
+        let m = self.sample_microfacet();
+        let wi = 2. * wo.dot(m) * m - wo;
+        if wo.z <= 0. || wi.z <= 0. {
+            // reflected below the surface
+            return Interaction::Absorbed;
+        }
+
+        // weight for sampling the distribution of normals is F * G * (wo.m) / ((wo.n) * (m.n))
+        let g = 1. / (1. + self.lambda(wo) + self.lambda(wi));
+        let weight = g * wo.dot(m) / (wo.z * m.z);
+        let r = (wi.x * t + wi.y * b + wi.z * n).normalize();
+        let ray = hit.incident.spawn(hit.position + 0.001 * r, r);
+        Interaction::Reflected {
+            ray,
+            attenuation: self.fresnel(wi.dot(m)) * weight,
+        }
+    }
+}
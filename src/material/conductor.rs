@@ -0,0 +1,161 @@
+use std::str::FromStr;
+
+// wavelengths in nm that the tables are sampled at
+const WAVELENGTHS: [f32; 7] = [400., 450., 500., 550., 600., 650., 700.];
+
+// representative wavelengths in nm for the red, green and blue channels
+const RGB_WAVELENGTHS: [f32; 3] = [630., 532., 465.];
+
+// measured complex refractive index (n, k) at each of WAVELENGTHS
+const GOLD: [(f32, f32); 7] = [
+    (1.658, 1.956),
+    (1.380, 1.910),
+    (0.970, 1.870),
+    (0.430, 2.450),
+    (0.250, 2.980),
+    (0.170, 3.580),
+    (0.160, 3.950),
+];
+const SILVER: [(f32, f32); 7] = [
+    (0.050, 2.100),
+    (0.040, 2.660),
+    (0.050, 3.130),
+    (0.059, 3.500),
+    (0.060, 4.000),
+    (0.056, 4.280),
+    (0.040, 4.800),
+];
+const COPPER: [(f32, f32); 7] = [
+    (1.180, 2.210),
+    (1.170, 2.400),
+    (1.130, 2.560),
+    (1.020, 2.580),
+    (0.300, 3.400),
+    (0.210, 3.670),
+    (0.210, 4.050),
+];
+const ALUMINIUM: [(f32, f32); 7] = [
+    (0.490, 4.860),
+    (0.620, 5.470),
+    (0.770, 6.080),
+    (0.960, 6.690),
+    (1.200, 7.260),
+    (1.470, 7.790),
+    (1.830, 8.310),
+];
+const CHROMIUM: [(f32, f32); 7] = [
+    (2.000, 3.000),
+    (2.400, 3.200),
+    (2.750, 3.300),
+    (3.100, 3.330),
+    (3.200, 3.330),
+    (3.200, 3.350),
+    (3.200, 3.400),
+];
+const IRON: [(f32, f32); 7] = [
+    (2.380, 2.860),
+    (2.560, 2.930),
+    (2.720, 2.970),
+    (2.870, 3.030),
+    (2.920, 3.080),
+    (2.920, 3.120),
+    (2.900, 3.190),
+];
+const NICKEL: [(f32, f32); 7] = [
+    (1.610, 2.360),
+    (1.670, 2.650),
+    (1.730, 2.940),
+    (1.800, 3.250),
+    (1.880, 3.520),
+    (1.970, 3.780),
+    (2.060, 4.040),
+];
+const PLATINUM: [(f32, f32); 7] = [
+    (1.720, 2.870),
+    (1.830, 3.120),
+    (1.940, 3.370),
+    (2.050, 3.610),
+    (2.160, 3.830),
+    (2.270, 4.050),
+    (2.380, 4.260),
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Conductor {
+    Gold,
+    Silver,
+    Copper,
+    Aluminium,
+    Chromium,
+    Iron,
+    Nickel,
+    Platinum,
+}
+
+impl Conductor {
+    fn table(&self) -> &'static [(f32, f32); 7] {
+        match self {
+            Conductor::Gold => &GOLD,
+            Conductor::Silver => &SILVER,
+            Conductor::Copper => &COPPER,
+            Conductor::Aluminium => &ALUMINIUM,
+            Conductor::Chromium => &CHROMIUM,
+            Conductor::Iron => &IRON,
+            Conductor::Nickel => &NICKEL,
+            Conductor::Platinum => &PLATINUM,
+        }
+    }
+
+    pub fn ior(&self) -> ([f32; 3], [f32; 3]) {
+        // interpolate the tables at the wavelength of each colour channel
+        let table = self.table();
+        let eta_k = RGB_WAVELENGTHS.map(|wavelength| {
+            let i = WAVELENGTHS
+                .iter()
+                .rposition(|&w| w <= wavelength)
+                .unwrap_or(0)
+                .min(WAVELENGTHS.len() - 2);
+            let f = (wavelength - WAVELENGTHS[i]) / (WAVELENGTHS[i + 1] - WAVELENGTHS[i]);
+            let (n0, k0) = table[i];
+            let (n1, k1) = table[i + 1];
+            (n0 + (n1 - n0) * f, k0 + (k1 - k0) * f)
+        });
+        (eta_k.map(|(n, _)| n), eta_k.map(|(_, k)| k))
+    }
+}
+
+impl FromStr for Conductor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim() {
+            "gold" | "au" => Ok(Conductor::Gold),
+            "silver" | "ag" => Ok(Conductor::Silver),
+            "copper" | "cu" => Ok(Conductor::Copper),
+            "aluminium" | "aluminum" | "al" => Ok(Conductor::Aluminium),
+            "chromium" | "chrome" | "cr" => Ok(Conductor::Chromium),
+            "iron" | "fe" => Ok(Conductor::Iron),
+            "nickel" | "ni" => Ok(Conductor::Nickel),
+            "platinum" | "pt" => Ok(Conductor::Platinum),
+            _ => Err(format!("Unknown conductor: {}", s)),
+        }
+    }
+}
+
+pub fn fresnel(cos: f32, eta: f32, k: f32) -> f32 {
+    // exact unpolarised Fresnel reflectance of a conductor with complex index eta + ik
+    let cos2 = cos.clamp(0., 1.).powi(2);
+    let sin2 = 1. - cos2;
+    let t0 = eta.powi(2) - k.powi(2) - sin2;
+    let a2_plus_b2 = (t0.powi(2) + 4. * eta.powi(2) * k.powi(2)).sqrt();
+    let a = (0.5 * (a2_plus_b2 + t0)).max(0.).sqrt();
+
+    let t1 = a2_plus_b2 + cos2;
+    let t2 = 2. * cos2.sqrt() * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2.powi(2);
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+    (rs + rp) / 2.
+}
@@ -0,0 +1,67 @@
+use glam::Vec3A;
+
+use super::Material;
+use crate::{
+    colour::Colour,
+    hit::Hit,
+    photonmap::{Interaction, PhotonMap},
+    scene::Scene,
+    texture::Texture,
+};
+
+#[derive(Debug)]
+pub struct Cutout {
+    material: Box<dyn Material + Send + Sync>,
+    opacity: Box<dyn Texture>,
+}
+
+impl Cutout {
+    pub fn new<M>(material: M, opacity: f32) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self::with_mask(material, Colour::from_rgb(opacity, opacity, opacity))
+    }
+
+    pub fn with_mask<M, T>(material: M, mask: T) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+        T: Texture + 'static,
+    {
+        Cutout {
+            material: Box::new(material),
+            opacity: Box::new(mask),
+        }
+    }
+}
+
+impl Material for Cutout {
+    fn compute(
+        &self,
+        viewer: Vec3A,
+        hit: &Hit,
+        recurse: usize,
+        scene: &Scene,
+        pmap: &PhotonMap,
+    ) -> Colour {
+        self.material.compute(viewer, hit, recurse, scene, pmap)
+    }
+
+    fn interact(&self, hit: &Hit) -> Interaction {
+        self.material.interact(hit)
+    }
+
+    fn opacity(&self, hit: &Hit) -> f32 {
+        // white in the mask is fully opaque, black is fully cut away
+        let m = self.opacity.value(hit);
+        ((m.r + m.g + m.b) / 3.).clamp(0., 1.) * self.material.opacity(hit)
+    }
+
+    fn transmits_shadow(&self, hit: &Hit) -> bool {
+        self.material.transmits_shadow(hit)
+    }
+
+    fn is_medium(&self) -> bool {
+        self.material.is_medium()
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
     hit::Hit,
     material::Material,
     photonmap::{Interaction, PhotonMap},
-    ray::{Ray, Reflectable},
+    ray::Reflectable,
     scene::Scene,
 };
 
@@ -76,18 +76,23 @@ impl Material for Dielectric {
 
         if ratio * sin_theta <= 1. && random::<f32>() > refl_probability {
             let r = Dielectric::refract(hit, ratio);
-            let ray = Ray::new(hit.position + 0.001 * r, r);
+            let ray = hit.incident.spawn(hit.position + 0.001 * r, r);
             Interaction::Transmitted {
                 ray,
                 attenuation: self.colour,
             }
         } else {
             let r = hit.incident.direction.reflect(hit.normal);
-            let ray = Ray::new(hit.position + 0.001 * r, r);
+            let ray = hit.incident.spawn(hit.position + 0.001 * r, r);
             Interaction::Reflected {
                 ray,
                 attenuation: self.colour,
             }
         }
     }
+
+    fn transmits_shadow(&self, hit: &Hit) -> bool {
+        // light refracted through the surface still reaches the point being shaded
+        matches!(self.interact(hit), Interaction::Transmitted { .. })
+    }
 }
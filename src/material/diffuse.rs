@@ -6,7 +6,6 @@ use crate::{
     hit::Hit,
     material::Material,
     photonmap::{Interaction, PhotonMap},
-    ray::Ray,
     scene::Scene,
 };
 
@@ -19,6 +18,14 @@ impl Diffuse {
     pub fn new(colour: Colour) -> Self {
         Diffuse { colour }
     }
+
+    fn albedo(&self, hit: &Hit) -> Colour {
+        // vertex colours tint the material's own colour
+        match hit.colour {
+            Some(c) => self.colour * c,
+            None => self.colour,
+        }
+    }
 }
 
 impl Material for Diffuse {
@@ -49,7 +56,10 @@ impl Material for Diffuse {
                     Some(pos) => (pos - hit.position).length(),
                 };
                 // check for objects between position and light
-                lit = !scene.shadow_trace(&Ray::new(hit.position + 0.0001 * ldir, ldir), limit);
+                lit = !scene.shadow_trace(
+                    &hit.incident.spawn(hit.position + 0.0001 * ldir, ldir),
+                    limit,
+                );
             }
 
             if lit {
@@ -58,7 +68,7 @@ impl Material for Diffuse {
                 let diffuse = if dotprod < 0. {
                     Colour::default()
                 } else {
-                    self.colour * dotprod
+                    self.albedo(hit) * dotprod
                 };
                 colour += intensity * diffuse;
             }
@@ -69,15 +79,16 @@ impl Material for Diffuse {
 
     fn interact(&self, hit: &Hit) -> Interaction {
         // darker objects are more likely to absorb light
-        let diffuse_p = (self.colour.r + self.colour.g + self.colour.b) / 3.;
+        let albedo = self.albedo(hit);
+        let diffuse_p = (albedo.r + albedo.g + albedo.b) / 3.;
         if random::<f32>() > diffuse_p {
             Interaction::Absorbed
         } else {
             let r = random_in_unit_hemisphere(hit.normal);
-            let ray = Ray::new(hit.position + 0.0001 * r, r);
+            let ray = hit.incident.spawn(hit.position + 0.0001 * r, r);
             Interaction::Reflected {
                 ray,
-                attenuation: self.colour,
+                attenuation: albedo,
             }
         }
     }
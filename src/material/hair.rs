@@ -0,0 +1,108 @@
+use glam::Vec3A;
+use rand::random;
+
+use super::Material;
+use crate::{
+    colour::Colour,
+    hit::Hit,
+    photonmap::{Interaction, PhotonMap},
+    scene::Scene,
+};
+
+// kajiya-kay shading, which lights fibres by their direction rather than their normal
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hair {
+    colour: Colour,
+    specular: Colour,
+    power: f32,
+}
+
+impl Hair {
+    pub fn new(colour: Colour, specular: Colour, power: f32) -> Self {
+        Hair {
+            colour,
+            specular,
+            power,
+        }
+    }
+
+    fn colour(&self, hit: &Hit) -> Colour {
+        match hit.colour {
+            Some(c) => self.colour * c,
+            None => self.colour,
+        }
+    }
+
+    fn compute_per_light(&self, viewer: Vec3A, hit: &Hit, ldir: Vec3A) -> Colour {
+        let tangent = hit.tangent;
+        let cos_light = tangent.dot(ldir);
+        let cos_eye = tangent.dot(viewer);
+        let sin_light = (1. - cos_light * cos_light).max(0.).sqrt();
+        let sin_eye = (1. - cos_eye * cos_eye).max(0.).sqrt();
+
+        // diffuse light falls off as the light lines up with the fibre
+        let diffuse = self.colour(hit) * sin_light;
+
+        // specular light peaks when the eye lies on the cone of mirror directions around it
+        let cone = (sin_light * sin_eye - cos_light * cos_eye).max(0.);
+        diffuse + self.specular * cone.powf(self.power)
+    }
+}
+
+impl Material for Hair {
+    fn compute(
+        &self,
+        viewer: Vec3A,
+        hit: &Hit,
+        _recurse: usize,
+        scene: &Scene,
+        _pmap: &PhotonMap,
+    ) -> Colour {
+        let ambient_intensity = 0.3;
+        let mut colour = self.colour(hit) * ambient_intensity;
+
+        for light in &scene.light_list {
+            // fibres are thin enough to be lit from either side
+            let (ldir, mut lit) = light.get_direction(hit.position);
+
+            if lit {
+                let limit = match light.get_position() {
+                    None => f32::INFINITY,
+                    Some(pos) => (pos - hit.position).length(),
+                };
+                lit = !scene.shadow_trace(
+                    &hit.incident.spawn(hit.position + 0.0001 * ldir, ldir),
+                    limit,
+                );
+            }
+
+            if lit {
+                colour +=
+                    light.get_intensity(hit.position) * self.compute_per_light(viewer, hit, ldir);
+            }
+        }
+        colour
+    }
+
+    fn interact(&self, hit: &Hit) -> Interaction {
+        let colour = self.colour(hit);
+        let p = (colour.r + colour.g + colour.b) / 3.;
+        if random::<f32>() > p {
+            Interaction::Absorbed
+        } else {
+            // light scatters in every direction off a thin fibre
+            let r = loop {
+                let v = Vec3A::new(random(), random(), random()) * 2. - 1.;
+                if v.length_squared() <= 1. {
+                    if let Some(v) = v.try_normalize() {
+                        break v;
+                    }
+                }
+            };
+            Interaction::Reflected {
+                ray: hit.incident.spawn(hit.position + 0.0001 * r, r),
+                attenuation: colour,
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::f32::consts::PI;
+
+use glam::Vec3A;
+use rand::random;
+
+use super::Material;
+use crate::{
+    colour::Colour,
+    hit::Hit,
+    photonmap::{Interaction, PhotonMap},
+    scene::Scene,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HenyeyGreenstein {
+    g: f32,
+    colour: Colour,
+    emission: Colour,
+}
+
+impl HenyeyGreenstein {
+    // g is the asymmetry parameter, positive values scatter forwards and negative values backwards
+    pub fn new(g: f32, colour: Colour) -> Self {
+        Self::with_emission(g, colour, Colour::from_rgb(0., 0., 0.))
+    }
+
+    pub fn with_emission(g: f32, colour: Colour, emission: Colour) -> Self {
+        HenyeyGreenstein {
+            g: g.clamp(-0.99, 0.99),
+            colour,
+            emission,
+        }
+    }
+
+    pub fn phase(&self, cos_theta: f32) -> f32 {
+        // probability density of scattering through an angle theta
+        let denom = 1. + self.g.powi(2) - 2. * self.g * cos_theta;
+        (1. - self.g.powi(2)) / (4. * PI * denom * denom.sqrt())
+    }
+
+    fn sample(&self, direction: Vec3A) -> Vec3A {
+        // importance sample the phase function about the direction of travel
+        let xi = random::<f32>();
+        let cos_theta = if self.g.abs() < 0.001 {
+            1. - 2. * xi
+        } else {
+            let s = (1. - self.g.powi(2)) / (1. - self.g + 2. * self.g * xi);
+            (1. + self.g.powi(2) - s.powi(2)) / (2. * self.g)
+        };
+        let sin_theta = (1. - cos_theta.powi(2)).max(0.).sqrt();
+        let phi = 2. * PI * random::<f32>();
+        let (u, v) = direction.any_orthonormal_pair();
+        (sin_theta * phi.cos() * u + sin_theta * phi.sin() * v + cos_theta * direction).normalize()
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn compute(
+        &self,
+        _viewer: Vec3A,
+        hit: &Hit,
+        recurse: usize,
+        scene: &Scene,
+        pmap: &PhotonMap,
+    ) -> Colour {
+        if recurse < 1 {
+            return Colour::default();
+        }
+
+        let direction = hit.incident.direction.normalize();
+        let mut colour = self.emission;
+        for light in &scene.light_list {
+            // ldir is direction towards the light
+            let (ldir, lit) = light.get_direction(hit.position);
+            if !lit {
+                continue;
+            }
+
+            let limit = match light.get_position() {
+                None => f32::INFINITY,
+                Some(pos) => (pos - hit.position).length(),
+            };
+            // check for objects and media between position and light
+            if !scene.shadow_trace(&hit.incident.spawn(hit.position, ldir), limit) {
+                // scaled so isotropic scattering matches a diffuse surface facing the light
+                let p = 4. * PI * self.phase(direction.dot(ldir));
+                colour += light.get_intensity(hit.position) * self.colour * p;
+            }
+        }
+
+        // light that reached here after bouncing, gathered from the volume photons
+        let indirect =
+            pmap.volume_radiance(hit.position, |d| 4. * PI * self.phase(d.dot(-direction)));
+        colour + indirect * self.colour
+    }
+
+    fn interact(&self, hit: &Hit) -> Interaction {
+        let r = self.sample(hit.incident.direction.normalize());
+        Interaction::Scattered {
+            ray: hit.incident.spawn(hit.position, r),
+            attenuation: self.colour,
+        }
+    }
+
+    fn is_medium(&self) -> bool {
+        true
+    }
+}
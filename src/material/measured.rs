@@ -0,0 +1,280 @@
+use std::{f32::consts::PI, fs};
+
+use glam::Vec3A;
+use rand::random;
+
+use super::Material;
+use crate::{
+    colour::Colour,
+    hit::Hit,
+    photonmap::{Interaction, PhotonMap},
+    scene::Scene,
+};
+
+// resolution of the MERL tables in theta half, theta diff and phi diff
+const THETA_HALF_RES: usize = 90;
+const THETA_DIFF_RES: usize = 90;
+const PHI_DIFF_RES: usize = 180;
+
+// MERL stores each channel with its own scale
+const SCALE: [f32; 3] = [1. / 1500., 1.15 / 1500., 1.66 / 1500.];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measured {
+    values: Vec<[f32; 3]>,
+    // discrete distribution over theta half bins used for importance sampling
+    theta_half_cdf: Vec<f32>,
+}
+
+impl Measured {
+    pub fn new(filename: &str) -> Self {
+        // MERL .binary files hold three i32 dimensions followed by f64 values for red,
+        // then green, then blue
+        let bytes = fs::read(filename).expect("Should read the file");
+        if bytes.len() < 12 {
+            panic!(
+                "BRDF file has {} bytes, too short for the 12 byte header",
+                bytes.len()
+            );
+        }
+        let dims = bytes[..12]
+            .chunks_exact(4)
+            .map(|d| i32::from_le_bytes([d[0], d[1], d[2], d[3]]) as usize)
+            .collect::<Vec<_>>();
+        if dims != [THETA_HALF_RES, THETA_DIFF_RES, PHI_DIFF_RES] {
+            panic!("BRDF file has dimensions {:?}, expected 90 90 180", dims);
+        }
+
+        let n = THETA_HALF_RES * THETA_DIFF_RES * PHI_DIFF_RES;
+        let raw = bytes[12..]
+            .chunks_exact(8)
+            .map(|d| f64::from_le_bytes([d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]]) as f32)
+            .collect::<Vec<_>>();
+        if raw.len() != 3 * n {
+            panic!("BRDF file has {} values, expected {}", raw.len(), 3 * n);
+        }
+        // negative entries mark angles that weren't measured
+        let values = (0..n)
+            .map(|i| [0, 1, 2].map(|c| (raw[i + c * n] * SCALE[c]).max(0.)))
+            .collect::<Vec<_>>();
+
+        Self::from_values(values)
+    }
+
+    // a table of rgb values indexed by theta half, then theta diff, then phi diff, at the
+    // MERL resolution and already scaled
+    pub fn from_values(values: Vec<[f32; 3]>) -> Self {
+        let n = THETA_HALF_RES * THETA_DIFF_RES * PHI_DIFF_RES;
+        if values.len() != n {
+            panic!("BRDF table has {} values, expected {}", values.len(), n);
+        }
+
+        // tabulate the average reflectance at each theta half, weighted by the projected
+        // solid angle the bin covers, as an approximation of the distribution of normals
+        let mut theta_half_cdf = Vec::with_capacity(THETA_HALF_RES + 1);
+        theta_half_cdf.push(0.);
+        let mut total = 0.;
+        for th in 0..THETA_HALF_RES {
+            let start = th * THETA_DIFF_RES * PHI_DIFF_RES;
+            let end = start + THETA_DIFF_RES * PHI_DIFF_RES;
+            let mean = values[start..end]
+                .iter()
+                .map(|v| (v[0] + v[1] + v[2]) / 3.)
+                .sum::<f32>()
+                / (end - start) as f32;
+            let (lo, hi) = (theta_half_edge(th), theta_half_edge(th + 1));
+            let theta = (lo + hi) / 2.;
+            // keep a small floor so every direction can be sampled
+            total += (mean + 0.0001) * theta.sin() * theta.cos() * (hi - lo);
+            theta_half_cdf.push(total);
+        }
+        for c in &mut theta_half_cdf {
+            *c /= total;
+        }
+        Measured {
+            values,
+            theta_half_cdf,
+        }
+    }
+
+    pub fn brdf(&self, wi: Vec3A, wo: Vec3A) -> Colour {
+        // evaluate the BRDF for local directions, trilinearly interpolating the table
+        if wi.z <= 0. || wo.z <= 0. {
+            return Colour::from_rgb(0., 0., 0.);
+        }
+        let (theta_half, theta_diff, phi_diff) = half_diff(wi, wo);
+
+        let th = ((theta_half / (PI / 2.)).max(0.).sqrt() * THETA_HALF_RES as f32)
+            .min(THETA_HALF_RES as f32 - 1.);
+        let td = (theta_diff / (PI / 2.) * THETA_DIFF_RES as f32).min(THETA_DIFF_RES as f32 - 1.);
+        // reciprocity makes phi diff symmetric, so only [0, pi) is stored
+        let pd = phi_diff.rem_euclid(PI) / PI * PHI_DIFF_RES as f32;
+
+        let (th0, td0, pd0) = (th as usize, td as usize, pd as usize % PHI_DIFF_RES);
+        let (th1, td1, pd1) = (
+            (th0 + 1).min(THETA_HALF_RES - 1),
+            (td0 + 1).min(THETA_DIFF_RES - 1),
+            (pd0 + 1) % PHI_DIFF_RES,
+        );
+        let (fh, fd, fp) = (th.fract(), td.fract(), pd.fract());
+
+        let mut result = [0.; 3];
+        for (h, wh) in [(th0, 1. - fh), (th1, fh)] {
+            for (d, wd) in [(td0, 1. - fd), (td1, fd)] {
+                for (p, wp) in [(pd0, 1. - fp), (pd1, fp)] {
+                    let v = self.values[(h * THETA_DIFF_RES + d) * PHI_DIFF_RES + p];
+                    for c in 0..3 {
+                        result[c] += wh * wd * wp * v[c];
+                    }
+                }
+            }
+        }
+        Colour::from_rgb(result[0], result[1], result[2])
+    }
+
+    fn sample(&self, wo: Vec3A) -> Vec3A {
+        // one sample from either a cosine lobe or the tabulated half vector distribution
+        if random::<f32>() < 0.5 {
+            let r = random::<f32>().sqrt();
+            let phi = 2. * PI * random::<f32>();
+            return Vec3A::new(r * phi.cos(), r * phi.sin(), (1. - r * r).max(0.).sqrt());
+        }
+
+        let xi = random::<f32>();
+        let bin = self
+            .theta_half_cdf
+            .partition_point(|&c| c <= xi)
+            .clamp(1, THETA_HALF_RES)
+            - 1;
+        let (lo, hi) = (theta_half_edge(bin), theta_half_edge(bin + 1));
+        let theta = lo + (hi - lo) * random::<f32>();
+        let phi = 2. * PI * random::<f32>();
+        let h = Vec3A::new(
+            theta.sin() * phi.cos(),
+            theta.sin() * phi.sin(),
+            theta.cos(),
+        );
+        2. * wo.dot(h) * h - wo
+    }
+
+    fn pdf(&self, wi: Vec3A, wo: Vec3A) -> f32 {
+        // density of sample() over solid angle, averaging both strategies
+        let cosine = wi.z.max(0.) / PI;
+
+        let h = (wi + wo).normalize();
+        let theta = h.z.clamp(-1., 1.).acos();
+        let bin = (((theta / (PI / 2.)).max(0.).sqrt() * THETA_HALF_RES as f32) as usize)
+            .min(THETA_HALF_RES - 1);
+        let (lo, hi) = (theta_half_edge(bin), theta_half_edge(bin + 1));
+        let p = self.theta_half_cdf[bin + 1] - self.theta_half_cdf[bin];
+        let pdf_h = p / (hi - lo) / (2. * PI * theta.sin().max(0.0001));
+        let tabulated = pdf_h / (4. * wo.dot(h).abs().max(0.0001));
+
+        0.5 * cosine + 0.5 * tabulated
+    }
+
+    fn frame(hit: &Hit) -> (Vec3A, Vec3A, Vec3A) {
+        let n = hit.normal;
+        let t = (hit.tangent - hit.tangent.dot(n) * n)
+            .try_normalize()
+            .unwrap_or_else(|| n.any_orthonormal_vector());
+        (t, n.cross(t), n)
+    }
+}
+
+impl Material for Measured {
+    fn compute(
+        &self,
+        viewer: Vec3A,
+        hit: &Hit,
+        recurse: usize,
+        scene: &Scene,
+        pmap: &PhotonMap,
+    ) -> Colour {
+        if recurse < 1 {
+            return Colour::default();
+        }
+        let (t, b, n) = Self::frame(hit);
+        let to_local = |w: Vec3A| Vec3A::new(w.dot(t), w.dot(b), w.dot(n));
+        let wo = to_local(-hit.incident.direction.normalize());
+
+        let mut colour = Colour::from_rgb(0., 0., 0.);
+        for light in &scene.light_list {
+            // ldir is direction towards the light
+            let (ldir, mut lit) = light.get_direction(hit.position);
+            if ldir.dot(hit.normal) < 0. {
+                // light is facing wrong way
+                lit = false;
+            }
+
+            if lit {
+                let limit = match light.get_position() {
+                    None => f32::INFINITY,
+                    Some(pos) => (pos - hit.position).length(),
+                };
+                // check for objects between position and light
+                lit = !scene.shadow_trace(
+                    &hit.incident.spawn(hit.position + 0.0001 * ldir, ldir),
+                    limit,
+                );
+            }
+
+            if lit {
+                // the table is used as measured, as it is for the sampled indirect light below
+                let wi = to_local(ldir);
+                let f = self.brdf(wi, wo) * wi.z;
+                colour += light.get_intensity(hit.position) * f;
+            }
+        }
+
+        // indirect light from a single importance sampled direction
+        if let Interaction::Reflected { ray, attenuation } = self.interact(hit) {
+            colour += scene.raytrace(ray, recurse - 1, viewer, pmap).0 * attenuation;
+        }
+        colour
+    }
+
+    fn interact(&self, hit: &Hit) -> Interaction {
+        let (t, b, n) = Self::frame(hit);
+        let to_local = |w: Vec3A| Vec3A::new(w.dot(t), w.dot(b), w.dot(n));
+        let wo = to_local(-hit.incident.direction.normalize());
+        let wi = self.sample(wo);
+        if wi.z <= 0. || wo.z <= 0. {
+            return Interaction::Absorbed;
+        }
+
+        let attenuation = self.brdf(wi, wo) * (wi.z / self.pdf(wi, wo));
+        let r = (wi.x * t + wi.y * b + wi.z * n).normalize();
+        let ray = hit.incident.spawn(hit.position + 0.0001 * r, r);
+        Interaction::Reflected { ray, attenuation }
+    }
+}
+
+fn theta_half_edge(i: usize) -> f32 {
+    // theta half bins are spaced quadratically to resolve the specular peak
+    (i as f32 / THETA_HALF_RES as f32).powi(2) * PI / 2.
+}
+
+fn half_diff(wi: Vec3A, wo: Vec3A) -> (f32, f32, f32) {
+    // convert a pair of local directions to Rusinkiewicz half/difference angles
+    let half = (wi + wo).normalize();
+    let theta_half = half.z.clamp(-1., 1.).acos();
+    let phi_half = half.y.atan2(half.x);
+
+    // rotate wi so the half vector lies along the normal
+    let (sin_p, cos_p) = (-phi_half).sin_cos();
+    let tmp = Vec3A::new(
+        wi.x * cos_p - wi.y * sin_p,
+        wi.x * sin_p + wi.y * cos_p,
+        wi.z,
+    );
+    let (sin_t, cos_t) = (-theta_half).sin_cos();
+    let diff = Vec3A::new(
+        tmp.x * cos_t + tmp.z * sin_t,
+        tmp.y,
+        -tmp.x * sin_t + tmp.z * cos_t,
+    );
+    let theta_diff = diff.z.clamp(-1., 1.).acos();
+    let phi_diff = diff.y.atan2(diff.x);
+    (theta_half, theta_diff, phi_diff)
+}
@@ -1,28 +1,61 @@
 use glam::Vec3A;
 
-use super::Material;
+use super::{
+    conductor::{fresnel, Conductor},
+    Material,
+};
 use crate::{
     colour::Colour,
     hit::Hit,
     light::point::random_in_unit_sphere,
     photonmap::{Interaction, PhotonMap},
-    ray::{Ray, Reflectable},
+    ray::Reflectable,
     scene::Scene,
 };
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Metallic {
-    colour: Colour,
+    reflectance: Reflectance,
     roughness: f32,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Reflectance {
+    Colour(Colour),
+    Conductor { eta: [f32; 3], k: [f32; 3] },
+}
+
 impl Metallic {
     pub fn new(specular: Colour, roughness: f32) -> Self {
         Metallic {
-            colour: specular,
+            reflectance: Reflectance::Colour(specular),
+            roughness,
+        }
+    }
+
+    pub fn from_ior(eta: [f32; 3], k: [f32; 3], roughness: f32) -> Self {
+        // complex refractive index per colour channel
+        Metallic {
+            reflectance: Reflectance::Conductor { eta, k },
             roughness,
         }
     }
+
+    pub fn preset(conductor: Conductor, roughness: f32) -> Self {
+        let (eta, k) = conductor.ior();
+        Self::from_ior(eta, k, roughness)
+    }
+
+    fn reflectance(&self, cos: f32) -> Colour {
+        match self.reflectance {
+            Reflectance::Colour(colour) => colour,
+            Reflectance::Conductor { eta, k } => Colour::from_rgb(
+                fresnel(cos, eta[0], k[0]),
+                fresnel(cos, eta[1], k[1]),
+                fresnel(cos, eta[2], k[2]),
+            ),
+        }
+    }
 }
 
 impl Material for Metallic {
@@ -48,10 +81,10 @@ impl Material for Metallic {
         let r = (hit.incident.direction.normalize().reflect(hit.normal)
             + self.roughness * random_in_unit_sphere())
         .normalize();
-        let ray = Ray::new(hit.position + 0.001 * r, r);
+        let ray = hit.incident.spawn(hit.position + 0.001 * r, r);
         Interaction::Reflected {
             ray,
-            attenuation: self.colour,
+            attenuation: self.reflectance(r.dot(hit.normal).abs()),
         }
     }
 }
@@ -0,0 +1,96 @@
+use glam::Vec3A;
+use rand::random;
+
+use super::Material;
+use crate::{
+    colour::Colour,
+    hit::Hit,
+    photonmap::{Interaction, PhotonMap},
+    scene::Scene,
+    texture::Texture,
+};
+
+#[derive(Debug)]
+pub struct Mix {
+    first: Box<dyn Material + Send + Sync>,
+    second: Box<dyn Material + Send + Sync>,
+    mask: Box<dyn Texture>,
+}
+
+impl Mix {
+    pub fn new<M1, M2>(first: M1, second: M2, weight: f32) -> Self
+    where
+        M1: Material + Send + Sync + 'static,
+        M2: Material + Send + Sync + 'static,
+    {
+        // weight is the proportion of the second material
+        Self::with_mask(first, second, Colour::from_rgb(weight, weight, weight))
+    }
+
+    pub fn with_mask<M1, M2, T>(first: M1, second: M2, mask: T) -> Self
+    where
+        M1: Material + Send + Sync + 'static,
+        M2: Material + Send + Sync + 'static,
+        T: Texture + 'static,
+    {
+        Mix {
+            first: Box::new(first),
+            second: Box::new(second),
+            mask: Box::new(mask),
+        }
+    }
+
+    fn weight(&self, hit: &Hit) -> f32 {
+        // average the mask channels into a single blend weight
+        let m = self.mask.value(hit);
+        ((m.r + m.g + m.b) / 3.).clamp(0., 1.)
+    }
+}
+
+impl Material for Mix {
+    fn compute(
+        &self,
+        viewer: Vec3A,
+        hit: &Hit,
+        recurse: usize,
+        scene: &Scene,
+        pmap: &PhotonMap,
+    ) -> Colour {
+        let w = self.weight(hit);
+        // skip evaluating a child that doesn't contribute
+        if w <= 0. {
+            return self.first.compute(viewer, hit, recurse, scene, pmap);
+        }
+        if w >= 1. {
+            return self.second.compute(viewer, hit, recurse, scene, pmap);
+        }
+        self.first.compute(viewer, hit, recurse, scene, pmap) * (1. - w)
+            + self.second.compute(viewer, hit, recurse, scene, pmap) * w
+    }
+
+    fn interact(&self, hit: &Hit) -> Interaction {
+        // pick one child so each photon follows a single consistent path
+        if random::<f32>() < self.weight(hit) {
+            self.second.interact(hit)
+        } else {
+            self.first.interact(hit)
+        }
+    }
+
+    fn opacity(&self, hit: &Hit) -> f32 {
+        let w = self.weight(hit);
+        self.first.opacity(hit) * (1. - w) + self.second.opacity(hit) * w
+    }
+
+    fn transmits_shadow(&self, hit: &Hit) -> bool {
+        if random::<f32>() < self.weight(hit) {
+            self.second.transmits_shadow(hit)
+        } else {
+            self.first.transmits_shadow(hit)
+        }
+    }
+
+    fn is_medium(&self) -> bool {
+        self.first.is_medium() || self.second.is_medium()
+    }
+}
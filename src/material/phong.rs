@@ -6,7 +6,7 @@ use crate::{
     colour::Colour,
     hit::Hit,
     photonmap::{Interaction, PhotonMap},
-    ray::{Ray, Reflectable},
+    ray::Reflectable,
     scene::Scene,
 };
 
@@ -28,13 +28,21 @@ impl Phong {
         }
     }
 
+    fn tint(colour: Colour, hit: &Hit) -> Colour {
+        // vertex colours tint the ambient and diffuse colours
+        match hit.colour {
+            Some(c) => colour * c,
+            None => colour,
+        }
+    }
+
     // diffuse and specular terms
     fn compute_per_light(&self, viewer: Vec3A, hit: &Hit, ldir: Vec3A) -> Colour {
         let dotprod = hit.normal.dot(ldir);
         let diffuse = if dotprod < 0. {
             Colour::default()
         } else {
-            self.diffuse * dotprod
+            Self::tint(self.diffuse, hit) * dotprod
         };
 
         let r = -ldir.reflect(hit.normal);
@@ -53,7 +61,7 @@ impl Material for Phong {
         _pmap: &PhotonMap,
     ) -> Colour {
         let ambient_intensity = 0.3;
-        let mut colour = self.ambient * ambient_intensity;
+        let mut colour = Self::tint(self.ambient, hit) * ambient_intensity;
 
         for light in &scene.light_list {
             // ldir is direction towards the light
@@ -65,29 +73,36 @@ impl Material for Phong {
             }
 
             if lit {
+                let limit = match light.get_position() {
+                    None => f32::INFINITY,
+                    Some(pos) => (pos - hit.position).length(),
+                };
                 // check for objects between position and light
-                lit = !scene
-                    .shadow_trace(&Ray::new(hit.position + 0.0001 * ldir, ldir), f32::INFINITY);
+                lit = !scene.shadow_trace(
+                    &hit.incident.spawn(hit.position + 0.0001 * ldir, ldir),
+                    limit,
+                );
             }
 
             if lit {
                 let intensity = light.get_intensity(hit.position);
-                colour += intensity * self.compute_per_light(viewer, &hit, ldir);
+                colour += intensity * self.compute_per_light(viewer, hit, ldir);
             }
         }
         colour
     }
 
     fn interact(&self, hit: &Hit) -> Interaction {
-        let diffuse_p = (self.diffuse.r + self.diffuse.g + self.diffuse.b) / 3.;
+        let diffuse = Self::tint(self.diffuse, hit);
+        let diffuse_p = (diffuse.r + diffuse.g + diffuse.b) / 3.;
         if random::<f32>() > diffuse_p {
             Interaction::Absorbed
         } else {
             let r = random_in_unit_hemisphere(hit.normal);
-            let ray = Ray::new(hit.position + 0.0001 * r, r);
+            let ray = hit.incident.spawn(hit.position + 0.0001 * r, r);
             Interaction::Reflected {
                 ray,
-                attenuation: self.diffuse,
+                attenuation: diffuse,
             }
         }
     }
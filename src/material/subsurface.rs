@@ -0,0 +1,144 @@
+use glam::Vec3A;
+use rand::random;
+
+use super::Material;
+use crate::{
+    colour::Colour,
+    hit::Hit,
+    light::point::random_in_unit_sphere,
+    photonmap::{Interaction, PhotonMap},
+    scene::Scene,
+    Vertex,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Subsurface {
+    scattering: Colour,
+    absorption: Colour,
+    mean_free_path: f32,
+}
+
+impl Subsurface {
+    // scattering and absorption coefficients set the colour of the material through their ratio
+    // per channel, the mean free path is the average distance a ray travels between events
+    // light only travels through closed solids, open surfaces scatter it diffusely instead
+    pub fn new(scattering: Colour, absorption: Colour, mean_free_path: f32) -> Self {
+        Subsurface {
+            scattering,
+            absorption,
+            mean_free_path,
+        }
+    }
+
+    fn albedo(&self) -> Colour {
+        // single scattering albedo per channel
+        Colour::from_rgb(
+            self.scattering.r / (self.scattering.r + self.absorption.r),
+            self.scattering.g / (self.scattering.g + self.absorption.g),
+            self.scattering.b / (self.scattering.b + self.absorption.b),
+        )
+    }
+
+    fn walk(&self, hit: &Hit) -> Option<(Vertex, Vec3A, Colour)> {
+        // random walk through the interior of the object hit, returning where it leaves
+        // the surface, the outward normal there and the throughput of the path, or None if
+        // the path is absorbed
+        let max_steps = 256;
+        let albedo = self.albedo();
+        let mut throughput = Colour::from_rgb(1., 1., 1.);
+
+        // enter the surface with a diffuse distribution about the inward normal
+        let inward = if hit.entering {
+            -hit.normal
+        } else {
+            hit.normal
+        };
+        let mut dir = (inward + random_in_unit_sphere().normalize()).normalize();
+        let mut position = hit.position;
+
+        for _ in 0..max_steps {
+            let ray = hit.incident.spawn(position + 0.0001 * dir, dir);
+            let Some(exit) = Scene::select_first(hit.object_hit.intersection(&ray)) else {
+                // open surfaces such as planes and triangles have no inside to walk through,
+                // so scatter off the side that was hit as a diffuse surface would
+                return Some((hit.position, -inward, albedo));
+            };
+
+            // sample the free flight distance from an exponential distribution
+            let d = -(1. - random::<f32>()).ln() * self.mean_free_path;
+            if d >= exit.t {
+                // hits from inside face inwards
+                return Some((exit.position, -exit.normal, throughput));
+            }
+
+            // scatter isotropically inside the medium
+            position = ray.position + d * dir;
+            throughput *= albedo;
+            let p = (throughput.r + throughput.g + throughput.b) / 3.;
+            if random::<f32>() > p {
+                return None;
+            }
+            throughput = throughput / p;
+            dir = random_in_unit_sphere().normalize();
+        }
+        None
+    }
+}
+
+impl Material for Subsurface {
+    fn compute(
+        &self,
+        _viewer: Vec3A,
+        hit: &Hit,
+        recurse: usize,
+        scene: &Scene,
+        _pmap: &PhotonMap,
+    ) -> Colour {
+        if recurse < 1 {
+            return Colour::default();
+        }
+        let Some((exit, normal, throughput)) = self.walk(hit) else {
+            return Colour::default();
+        };
+
+        // gather direct light where the walk leaves the object
+        let mut colour = Colour::from_rgb(0., 0., 0.);
+        for light in &scene.light_list {
+            // ldir is direction towards the light
+            let (ldir, mut lit) = light.get_direction(exit);
+            let dotprod = normal.dot(ldir);
+            if dotprod < 0. {
+                // light is facing wrong way
+                lit = false;
+            }
+
+            if lit {
+                let limit = match light.get_position() {
+                    None => f32::INFINITY,
+                    Some(pos) => (pos - exit).length(),
+                };
+                // check for objects between exit point and light
+                lit = !scene.shadow_trace(&hit.incident.spawn(exit + 0.0001 * ldir, ldir), limit);
+            }
+
+            if lit {
+                colour += light.get_intensity(exit) * throughput * dotprod;
+            }
+        }
+        colour
+    }
+
+    fn interact(&self, hit: &Hit) -> Interaction {
+        if let Some((exit, normal, throughput)) = self.walk(hit) {
+            // leave the surface diffusely about the outward normal
+            let r = (normal + random_in_unit_sphere().normalize()).normalize();
+            let ray = hit.incident.spawn(exit + 0.0001 * r, r);
+            Interaction::Transmitted {
+                ray,
+                attenuation: throughput,
+            }
+        } else {
+            Interaction::Absorbed
+        }
+    }
+}
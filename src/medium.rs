@@ -0,0 +1,20 @@
+use std::fmt::Debug;
+
+use glam::Affine3A;
+
+use crate::{material::Material, ray::Ray};
+
+pub mod density;
+pub mod heterogeneous;
+pub mod homogeneous;
+
+pub trait Medium: Debug + Send + Sync {
+    // sample the distance to the next scattering event along the ray between t_min and t_max
+    fn sample_distance(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<f32>;
+
+    // material used to shade scattering events inside the medium
+    fn phase(&self) -> &(dyn Material + Send + Sync);
+
+    // transform the medium, media that fill their boundary are unaffected
+    fn apply_transform(&mut self, _t: Affine3A) {}
+}
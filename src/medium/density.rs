@@ -0,0 +1,14 @@
+use std::fmt::Debug;
+
+use crate::Vertex;
+
+pub mod grid;
+pub mod noise;
+
+pub trait Density: Debug + Send + Sync {
+    // density at a point in the unit cube the medium is defined over
+    fn density(&self, p: Vertex) -> f32;
+
+    // upper bound on the density, used as the majorant for delta tracking
+    fn max_density(&self) -> f32;
+}
@@ -0,0 +1,78 @@
+use std::fs;
+
+use super::Density;
+use crate::Vertex;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityGrid {
+    size: [usize; 3],
+    values: Vec<f32>,
+    max: f32,
+}
+
+impl DensityGrid {
+    pub fn new(filename: &str) -> Self {
+        // raw grid files hold the x, y and z resolution as little-endian u32s
+        // followed by every density as a little-endian f32, with x varying fastest
+        let bytes = fs::read(filename).expect("Should read the file");
+        let mut words = bytes.chunks_exact(4).map(|w| [w[0], w[1], w[2], w[3]]);
+        let size = [0; 3].map(|_| {
+            u32::from_le_bytes(words.next().expect("Grid file has a 12 byte header")) as usize
+        });
+        let values = words.map(f32::from_le_bytes).collect::<Vec<_>>();
+        Self::from_values(size, values)
+    }
+
+    pub fn from_values(size: [usize; 3], values: Vec<f32>) -> Self {
+        if size.contains(&0) {
+            panic!(
+                "Grid of size {:?} is empty, every axis needs a sample",
+                size
+            );
+        }
+        if values.len() != size[0] * size[1] * size[2] {
+            panic!(
+                "Grid of size {:?} needs {} values, found {}",
+                size,
+                size[0] * size[1] * size[2],
+                values.len()
+            );
+        }
+        let max = values.iter().copied().fold(0., f32::max);
+        DensityGrid { size, values, max }
+    }
+
+    fn get(&self, x: usize, y: usize, z: usize) -> f32 {
+        self.values[(z * self.size[1] + y) * self.size[0] + x]
+    }
+}
+
+impl Density for DensityGrid {
+    fn density(&self, p: Vertex) -> f32 {
+        if p.cmplt(Vertex::ZERO).any() || p.cmpgt(Vertex::ONE).any() {
+            return 0.;
+        }
+
+        // trilinear interpolation between the samples surrounding the point
+        let g = p
+            * (Vertex::new(
+                self.size[0] as f32,
+                self.size[1] as f32,
+                self.size[2] as f32,
+            ) - 1.);
+        let [x0, y0, z0] = [0, 1, 2].map(|i| (g[i].floor() as usize).min(self.size[i] - 1));
+        let [x1, y1, z1] = [(x0, 0), (y0, 1), (z0, 2)].map(|(c, i)| (c + 1).min(self.size[i] - 1));
+        let f = g - Vertex::new(x0 as f32, y0 as f32, z0 as f32);
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let c00 = lerp(self.get(x0, y0, z0), self.get(x1, y0, z0), f.x);
+        let c10 = lerp(self.get(x0, y1, z0), self.get(x1, y1, z0), f.x);
+        let c01 = lerp(self.get(x0, y0, z1), self.get(x1, y0, z1), f.x);
+        let c11 = lerp(self.get(x0, y1, z1), self.get(x1, y1, z1), f.x);
+        lerp(lerp(c00, c10, f.y), lerp(c01, c11, f.y), f.z)
+    }
+
+    fn max_density(&self) -> f32 {
+        self.max
+    }
+}
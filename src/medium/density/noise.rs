@@ -0,0 +1,75 @@
+use rand::{seq::SliceRandom, thread_rng};
+
+use super::Density;
+use crate::{light::point::random_in_unit_sphere, Vertex};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise {
+    permutation: Vec<usize>,
+    gradients: Vec<Vertex>,
+    scale: f32,
+    octaves: usize,
+}
+
+impl Noise {
+    pub fn new(scale: f32, octaves: usize) -> Self {
+        // randomised Perlin noise lattice, summed over octaves for a cloudy look
+        let mut rng = thread_rng();
+        let mut permutation = (0..256).collect::<Vec<_>>();
+        permutation.shuffle(&mut rng);
+        let gradients = (0..256)
+            .map(|_| random_in_unit_sphere().normalize())
+            .collect();
+        Noise {
+            permutation,
+            gradients,
+            scale,
+            octaves,
+        }
+    }
+
+    fn gradient(&self, x: i32, y: i32, z: i32) -> Vertex {
+        let hash = self.permutation[(x & 255) as usize];
+        let hash = self.permutation[(hash + (y & 255) as usize) & 255];
+        let hash = self.permutation[(hash + (z & 255) as usize) & 255];
+        self.gradients[hash]
+    }
+
+    fn perlin(&self, p: Vertex) -> f32 {
+        let cell = p.floor();
+        let f = p - cell;
+        // smootherstep fade so the noise has continuous derivatives
+        let fade = f * f * f * (f * (f * 6. - 15.) + 10.);
+        let [x, y, z] = [cell.x as i32, cell.y as i32, cell.z as i32];
+
+        let mut sum = 0.;
+        for (dx, dy, dz) in (0..8).map(|i| (i & 1, (i >> 1) & 1, (i >> 2) & 1)) {
+            let offset = Vertex::new(dx as f32, dy as f32, dz as f32);
+            let weight = Vertex::select(offset.cmpeq(Vertex::ONE), fade, 1. - fade);
+            sum += weight.x
+                * weight.y
+                * weight.z
+                * self.gradient(x + dx, y + dy, z + dz).dot(f - offset);
+        }
+        sum
+    }
+}
+
+impl Density for Noise {
+    fn density(&self, p: Vertex) -> f32 {
+        let mut sum = 0.;
+        let mut amplitude = 0.5;
+        let mut frequency = self.scale;
+        for _ in 0..self.octaves {
+            sum += amplitude * self.perlin(p * frequency);
+            amplitude *= 0.5;
+            frequency *= 2.;
+        }
+        // only positive noise is kept so clouds have clear gaps between them
+        (2. * sum).clamp(0., 1.)
+    }
+
+    fn max_density(&self) -> f32 {
+        1.
+    }
+}
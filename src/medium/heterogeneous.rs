@@ -0,0 +1,89 @@
+use glam::{Affine3A, Vec3A};
+use rand::random;
+
+use super::{density::Density, Medium};
+use crate::{
+    colour::Colour,
+    material::{henyeygreenstein::HenyeyGreenstein, Material},
+    ray::Ray,
+};
+
+#[derive(Debug)]
+pub struct Heterogeneous {
+    density: Box<dyn Density>,
+    transform: Affine3A,
+    inverse: Affine3A,
+    extinction: f32,
+    phase: HenyeyGreenstein,
+}
+
+impl Heterogeneous {
+    // the density is defined over the unit cube, which the transform places in the scene
+    pub fn new<D>(
+        density: D,
+        transform: Affine3A,
+        absorption: f32,
+        scattering: f32,
+        g: f32,
+        colour: Colour,
+        emission: Colour,
+    ) -> Self
+    where
+        D: Density + 'static,
+    {
+        // each collision either scatters light or absorbs and emits in proportion to the coefficients
+        let albedo = scattering / (absorption + scattering);
+        Heterogeneous {
+            density: Box::new(density),
+            transform,
+            inverse: transform.inverse(),
+            extinction: absorption + scattering,
+            phase: HenyeyGreenstein::with_emission(g, colour * albedo, emission * (1. - albedo)),
+        }
+    }
+
+    fn clip(position: Vec3A, direction: Vec3A) -> (f32, f32) {
+        // slab test against the unit cube in local space
+        let inv_dir = direction.recip();
+        let t0 = (Vec3A::ZERO - position) * inv_dir;
+        let t1 = (Vec3A::ONE - position) * inv_dir;
+        (t0.min(t1).max_element(), t0.max(t1).min_element())
+    }
+}
+
+impl Medium for Heterogeneous {
+    fn sample_distance(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<f32> {
+        // affine maps preserve the ray parameter, so t is shared between spaces
+        let position = self.inverse.transform_point3a(ray.position);
+        let direction = self.inverse.transform_vector3a(ray.direction);
+        let (near, far) = Self::clip(position, direction);
+        let t_max = t_max.min(far);
+
+        // delta tracking against the majorant extinction
+        let majorant = self.extinction * self.density.max_density() * ray.direction.length();
+        if majorant <= 0. {
+            return None;
+        }
+        let mut t = t_min.max(near);
+        loop {
+            t -= (1. - random::<f32>()).ln() / majorant;
+            if t >= t_max {
+                return None;
+            }
+            // accept real collisions, null collisions carry on unchanged
+            let density = self.density.density(position + t * direction);
+            if random::<f32>() * self.density.max_density() < density {
+                return Some(t);
+            }
+        }
+    }
+
+    fn phase(&self) -> &(dyn Material + Send + Sync) {
+        &self.phase
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform = t * self.transform;
+        self.inverse = self.transform.inverse();
+    }
+}
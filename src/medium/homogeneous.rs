@@ -0,0 +1,47 @@
+use rand::random;
+
+use super::Medium;
+use crate::{
+    colour::Colour,
+    material::{henyeygreenstein::HenyeyGreenstein, Material},
+    ray::Ray,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Homogeneous {
+    absorption: f32,
+    scattering: f32,
+    phase: HenyeyGreenstein,
+}
+
+impl Homogeneous {
+    pub fn new(absorption: f32, scattering: f32, g: f32, colour: Colour) -> Self {
+        // the chance of a collision being a scattering event darkens the phase function
+        let albedo = scattering / (absorption + scattering);
+        Homogeneous {
+            absorption,
+            scattering,
+            phase: HenyeyGreenstein::new(g, colour * albedo),
+        }
+    }
+}
+
+impl Medium for Homogeneous {
+    fn sample_distance(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<f32> {
+        let extinction = self.absorption + self.scattering;
+        if extinction <= 0. {
+            return None;
+        }
+        // free flight distances are exponentially distributed in a constant density
+        let d = -(1. - random::<f32>()).ln() / (extinction * ray.direction.length());
+        if t_min + d < t_max {
+            Some(t_min + d)
+        } else {
+            None
+        }
+    }
+
+    fn phase(&self) -> &(dyn Material + Send + Sync) {
+        &self.phase
+    }
+}
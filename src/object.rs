@@ -2,19 +2,44 @@ use std::fmt::Debug;
 
 use glam::Affine3A;
 
-use crate::{hit::Hit, ray::Ray};
+use crate::{aabb::Aabb, hit::Hit, ray::Ray};
 
+pub mod axisbox;
+pub mod bezier;
+pub mod cone;
 pub mod csg;
+pub mod curves;
+pub mod cylinder;
+pub mod disk;
+pub mod heightfield;
+pub mod instance;
+pub mod metaballs;
+pub mod moving;
 pub mod plane;
 pub mod polymesh;
+pub mod quad;
 pub mod quadratic;
+pub mod sdf;
 pub mod sphere;
+pub mod torus;
 pub mod triangle;
+pub mod volume;
 
 pub trait Object: Debug {
     // find intersections between the object and the ray if they exist
-    fn intersection(&self, ray: &Ray) -> Vec<Hit>;
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>>;
 
     // transform the object
     fn apply_transform(&mut self, t: Affine3A);
+
+    // box containing the object, or none if it is unbounded
+    fn bounds(&self) -> Option<Aabb> {
+        None
+    }
+
+    // whether a ray that crosses none of the object's surfaces is inside it all along, as
+    // rays running parallel to a plane on its inner side are
+    fn encloses(&self, _ray: &Ray) -> bool {
+        false
+    }
 }
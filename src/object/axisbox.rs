@@ -0,0 +1,93 @@
+use glam::{Affine3A, Vec2, Vec3A};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::Hit,
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+#[derive(Debug)]
+pub struct AxisBox {
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl AxisBox {
+    pub fn new<M>(min: Vertex, max: Vertex, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // the box is stored as a cube from -1 to 1 in local space
+        let center = (min + max) / 2.;
+        let half_size = (max - min).abs() / 2.;
+        Self {
+            transform: Transform::new(Affine3A::from_scale_rotation_translation(
+                half_size.into(),
+                Default::default(),
+                center.into(),
+            )),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Object for AxisBox {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let local = self.transform.ray_to_local(ray);
+
+        // slab test, remembering which face bounds each end of the interval
+        let mut near = (f32::NEG_INFINITY, 0);
+        let mut far = (f32::INFINITY, 0);
+        for axis in 0..3 {
+            let inv = 1. / local.direction[axis];
+            let mut t0 = (-1. - local.position[axis]) * inv;
+            let mut t1 = (1. - local.position[axis]) * inv;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > near.0 {
+                near = (t0, axis);
+            }
+            if t1 < far.0 {
+                far = (t1, axis);
+            }
+        }
+        if near.0 > far.0 || near.0.is_infinite() || far.0.is_infinite() {
+            return Vec::new();
+        }
+
+        let face_hit = |t: f32, axis: usize| {
+            let p = local.position + t * local.direction;
+            let mut normal = Vec3A::ZERO;
+            normal[axis] = p[axis].signum();
+            // the face coordinates are the other two axes in order
+            let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+            let mut tangent = Vec3A::ZERO;
+            tangent[a] = 1.;
+            LocalHit {
+                t,
+                normal,
+                tangent,
+                uv: Vec2::new((p[a] + 1.) / 2., (p[b] + 1.) / 2.),
+            }
+        };
+        self.transform.hits_to_world(
+            ray,
+            vec![face_hit(near.0, near.1), face_hit(far.0, far.1)],
+            self,
+            &*self.material,
+        )
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Aabb::new(-Vec3A::ONE, Vec3A::ONE).transformed(self.transform.to_world()))
+    }
+}
@@ -0,0 +1,311 @@
+use std::fs;
+
+use glam::{Affine3A, Mat3A, Vec2, Vec3A};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::Hit,
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+// levels of subdivision below each patch, giving 4^depth leaves
+const DEPTH: usize = 4;
+
+type ControlNet = [[Vertex; 4]; 4];
+
+#[derive(Debug)]
+pub struct Bezier {
+    patches: Vec<Patch>,
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+#[derive(Debug)]
+struct Patch {
+    net: ControlNet,
+    root: Node,
+}
+
+#[derive(Debug)]
+struct Node {
+    // padded so flat patches still have volume
+    bounds: Aabb,
+    u: (f32, f32),
+    v: (f32, f32),
+    // the corners of the sub-patch, which the surface passes through
+    corners: [Vertex; 4],
+    children: Vec<Node>,
+}
+
+impl Bezier {
+    pub fn new<M>(filename: &str, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // newell's format lists the patch count, then 16 one-indexed control points per
+        // patch, then the vertex count and the vertices, all separated by commas
+        let contents = fs::read_to_string(filename).expect("Should read the file");
+        let mut lines = contents.lines().filter(|l| !l.trim().is_empty());
+        let mut numbers = |ln: &str| -> Vec<f32> {
+            lines
+                .next()
+                .unwrap_or_else(|| panic!("Valid line, {}", ln))
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|n| !n.is_empty())
+                .map(|n| n.parse::<f32>().expect("Values are numbers"))
+                .collect()
+        };
+
+        let patch_count = numbers("patch count")[0] as usize;
+        let indices = (0..patch_count)
+            .map(|p| numbers(&format!("patch {}", p)))
+            .collect::<Vec<_>>();
+        let vertex_count = numbers("vertex count")[0] as usize;
+        let vertices = (0..vertex_count)
+            .map(|v| match numbers(&format!("vertex {}", v))[..] {
+                [x, y, z] => Vertex::new(x, y, z),
+                _ => panic!("Vertex {} should have 3 coordinates", v),
+            })
+            .collect::<Vec<_>>();
+
+        let nets = indices
+            .iter()
+            .map(|patch| {
+                if patch.len() != 16 {
+                    panic!("Patch should have 16 control points, found {}", patch.len());
+                }
+                let point = |i: usize, j: usize| vertices[patch[i * 4 + j] as usize - 1];
+                [0, 1, 2, 3].map(|i| [0, 1, 2, 3].map(|j| point(i, j)))
+            })
+            .collect();
+        Self::from_nets(nets, material)
+    }
+
+    // each net is indexed by u then v
+    pub fn from_nets<M>(nets: Vec<[[Vertex; 4]; 4]>, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            patches: nets
+                .into_iter()
+                .map(|net| Patch {
+                    net,
+                    root: Node::build(&net, (0., 1.), (0., 1.), DEPTH),
+                })
+                .collect(),
+            transform: Transform::default(),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Object for Bezier {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let local = self.transform.ray_to_local(ray);
+        let mut hits = Vec::new();
+        for patch in &self.patches {
+            let mut found: Vec<LocalHit> = Vec::new();
+            patch.intersect(&patch.root, &local, &mut found);
+            // neighbouring leaves can converge on the same point
+            found.sort_by(|a, b| a.t.total_cmp(&b.t));
+            found.dedup_by(|a, b| (a.t - b.t).abs() < 0.0001 && (a.uv - b.uv).length() < 0.001);
+            hits.extend(found);
+        }
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        // the surface lies inside the convex hull of its control points
+        let local = Aabb::from_points(self.patches.iter().flat_map(|p| p.net.iter().flatten()));
+        Some(local.transformed(self.transform.to_world()))
+    }
+}
+
+impl Node {
+    fn build(net: &ControlNet, u: (f32, f32), v: (f32, f32), depth: usize) -> Self {
+        // the surface lies inside the convex hull of its control points
+        let bounds = Aabb::from_points(net.iter().flatten()).pad(0.0001);
+
+        let children = if depth == 0 {
+            Vec::new()
+        } else {
+            let (u_mid, v_mid) = ((u.0 + u.1) / 2., (v.0 + v.1) / 2.);
+            let (low, high) = split_u(net);
+            let (ll, lh) = split_v(&low);
+            let (hl, hh) = split_v(&high);
+            vec![
+                Node::build(&ll, (u.0, u_mid), (v.0, v_mid), depth - 1),
+                Node::build(&lh, (u.0, u_mid), (v_mid, v.1), depth - 1),
+                Node::build(&hl, (u_mid, u.1), (v.0, v_mid), depth - 1),
+                Node::build(&hh, (u_mid, u.1), (v_mid, v.1), depth - 1),
+            ]
+        };
+
+        Node {
+            bounds,
+            u,
+            v,
+            corners: [net[0][0], net[3][0], net[3][3], net[0][3]],
+            children,
+        }
+    }
+
+    fn guess(&self, ray: &Ray) -> Vec2 {
+        // intersect the two triangles spanning the corners to estimate the parameters,
+        // falling back to the middle of the sub-patch
+        let [c00, c10, c11, c01] = self.corners;
+        let params = [
+            Vec2::new(0., 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(1., 1.),
+            Vec2::new(0., 1.),
+        ];
+        for [a, b, c] in [[0, 1, 2], [0, 2, 3]] {
+            let p = [c00, c10, c11, c01];
+            let e1 = p[b] - p[a];
+            let e2 = p[c] - p[a];
+            let h = ray.direction.cross(e2);
+            let det = e1.dot(h);
+            if det.abs() < 0.0000001 {
+                continue;
+            }
+            let s = ray.position - p[a];
+            let bu = s.dot(h) / det;
+            let bv = ray.direction.dot(s.cross(e1)) / det;
+            if bu >= 0. && bv >= 0. && bu + bv <= 1. {
+                let local = params[a] * (1. - bu - bv) + params[b] * bu + params[c] * bv;
+                return Vec2::new(
+                    self.u.0 + local.x * (self.u.1 - self.u.0),
+                    self.v.0 + local.y * (self.v.1 - self.v.0),
+                );
+            }
+        }
+        Vec2::new((self.u.0 + self.u.1) / 2., (self.v.0 + self.v.1) / 2.)
+    }
+}
+
+impl Patch {
+    fn intersect(&self, node: &Node, ray: &Ray, hits: &mut Vec<LocalHit>) {
+        if node.bounds.hit_by(ray).is_none() {
+            return;
+        }
+        if !node.children.is_empty() {
+            for child in &node.children {
+                self.intersect(child, ray, hits);
+            }
+            return;
+        }
+
+        // newton's method on p(u, v) = o + t d, starting from the flat estimate
+        let mut uv = node.guess(ray);
+        let (p, _, _) = self.evaluate(uv);
+        let mut t = (p - ray.position).dot(ray.direction) / ray.direction.length_squared();
+        let scale = node.bounds.size().max_element().max(0.001);
+        for _ in 0..10 {
+            let (p, du, dv) = self.evaluate(uv);
+            let error = p - (ray.position + t * ray.direction);
+            if error.length() < scale * 0.00001 {
+                // only the leaf owning the parameters reports the hit
+                let tolerance = 0.0001;
+                let inside =
+                    |x: f32, (lo, hi): (f32, f32)| x >= lo - tolerance && x <= hi + tolerance;
+                if inside(uv.x, node.u) && inside(uv.y, node.v) {
+                    hits.push(LocalHit {
+                        t,
+                        normal: self.normal(uv),
+                        tangent: du.try_normalize().unwrap_or(Vec3A::X),
+                        uv,
+                    });
+                }
+                return;
+            }
+            let jacobian = Mat3A::from_cols(du, dv, -ray.direction);
+            if jacobian.determinant().abs() < 1e-12 {
+                return;
+            }
+            let step = jacobian.inverse() * error;
+            uv = (uv - Vec2::new(step.x, step.y)).clamp(Vec2::ZERO, Vec2::ONE);
+            t -= step.z;
+        }
+    }
+
+    fn evaluate(&self, uv: Vec2) -> (Vertex, Vec3A, Vec3A) {
+        // the point and its derivatives from the cubic bernstein polynomials
+        let (bu, dbu) = bernstein(uv.x);
+        let (bv, dbv) = bernstein(uv.y);
+        let mut p = Vec3A::ZERO;
+        let mut du = Vec3A::ZERO;
+        let mut dv = Vec3A::ZERO;
+        for i in 0..4 {
+            for j in 0..4 {
+                let c = self.net[i][j];
+                p += c * bu[i] * bv[j];
+                du += c * dbu[i] * bv[j];
+                dv += c * bu[i] * dbv[j];
+            }
+        }
+        (p, du, dv)
+    }
+
+    fn normal(&self, uv: Vec2) -> Vec3A {
+        let (_, du, dv) = self.evaluate(uv);
+        du.cross(dv).try_normalize().unwrap_or_else(|| {
+            // a collapsed edge such as the teapot's lid has no normal on it, so use one nearby
+            let inwards = uv + (Vec2::splat(0.5) - uv) * 0.001;
+            let (_, du, dv) = self.evaluate(inwards);
+            du.cross(dv).try_normalize().unwrap_or(Vec3A::Y)
+        })
+    }
+}
+
+pub(crate) fn bernstein(t: f32) -> ([f32; 4], [f32; 4]) {
+    let s = 1. - t;
+    (
+        [s * s * s, 3. * t * s * s, 3. * t * t * s, t * t * t],
+        [
+            -3. * s * s,
+            3. * s * s - 6. * t * s,
+            6. * t * s - 3. * t * t,
+            3. * t * t,
+        ],
+    )
+}
+
+pub(crate) fn split_curve(c: [Vertex; 4]) -> ([Vertex; 4], [Vertex; 4]) {
+    // de casteljau's algorithm at the midpoint
+    let m01 = (c[0] + c[1]) / 2.;
+    let m12 = (c[1] + c[2]) / 2.;
+    let m23 = (c[2] + c[3]) / 2.;
+    let a = (m01 + m12) / 2.;
+    let b = (m12 + m23) / 2.;
+    let mid = (a + b) / 2.;
+    ([c[0], m01, a, mid], [mid, b, m23, c[3]])
+}
+
+fn split_u(net: &ControlNet) -> (ControlNet, ControlNet) {
+    let mut low = *net;
+    let mut high = *net;
+    for j in 0..4 {
+        let (l, h) = split_curve([net[0][j], net[1][j], net[2][j], net[3][j]]);
+        for i in 0..4 {
+            low[i][j] = l[i];
+            high[i][j] = h[i];
+        }
+    }
+    (low, high)
+}
+
+fn split_v(net: &ControlNet) -> (ControlNet, ControlNet) {
+    let split = net.map(split_curve);
+    (split.map(|s| s.0), split.map(|s| s.1))
+}
@@ -0,0 +1,93 @@
+use std::f32::consts::PI;
+
+use glam::{Affine3A, Vec2, Vec3A};
+
+use super::{
+    cylinder::{axis_frame, caps},
+    Object,
+};
+use crate::{
+    aabb::Aabb,
+    hit::{tangent_around_y, Hit},
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+#[derive(Debug)]
+pub struct Cone {
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Cone {
+    // a cone with a capped base of the given radius and its apex at base + axis
+    pub fn new<M>(base: Vertex, axis: Vec3A, radius: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            transform: Transform::new(axis_frame(base, axis, radius)),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Object for Cone {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        // local cone has radius 1 at y = 0 narrowing to the apex at y = 1
+        let local = self.transform.ray_to_local(ray);
+        let (p, d) = (local.position, local.direction);
+        let h = 1. - p.y;
+        let mut hits = Vec::new();
+
+        let a = d.x.powi(2) + d.z.powi(2) - d.y.powi(2);
+        let b = 2. * (p.x * d.x + p.z * d.z + h * d.y);
+        let c = p.x.powi(2) + p.z.powi(2) - h.powi(2);
+        let roots = if a.abs() < 0.0000001 {
+            // ray parallel to the side, so only one root
+            if b == 0. {
+                vec![]
+            } else {
+                vec![-c / b]
+            }
+        } else {
+            let disc = b.powi(2) - 4. * a * c;
+            if disc < 0. {
+                vec![]
+            } else {
+                let ds = disc.sqrt();
+                vec![(-b - ds) / (2. * a), (-b + ds) / (2. * a)]
+            }
+        };
+
+        for t in roots {
+            let q = p + t * d;
+            if (0. ..=1.).contains(&q.y) {
+                let normal = Vec3A::new(q.x, 1. - q.y, q.z)
+                    .try_normalize()
+                    .unwrap_or(Vec3A::Y);
+                hits.push(LocalHit {
+                    t,
+                    normal,
+                    tangent: tangent_around_y(normal),
+                    uv: Vec2::new(0.5 + q.z.atan2(q.x) / (2. * PI), q.y),
+                });
+            }
+        }
+
+        hits.extend(caps(p, d, &[(0., -1.)]));
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let local = Aabb::new(Vec3A::new(-1., 0., -1.), Vec3A::ONE);
+        Some(local.transformed(self.transform.to_world()))
+    }
+}
@@ -1,12 +1,13 @@
-use std::{cmp::Ordering, collections::VecDeque};
-
 use glam::Affine3A;
 
 use crate::{
+    aabb::Aabb,
     hit::Hit,
     object::{
-        plane::Plane, polymesh::PolyMesh, quadratic::Quadratic, sphere::Sphere, triangle::Triangle,
-        Object,
+        axisbox::AxisBox, bezier::Bezier, cone::Cone, curves::Curves, cylinder::Cylinder,
+        disk::Disk, heightfield::Heightfield, instance::Instance, metaballs::Metaballs,
+        moving::Moving, plane::Plane, polymesh::PolyMesh, quad::Quad, quadratic::Quadratic,
+        sdf::Sdf, sphere::Sphere, torus::Torus, triangle::Triangle, Object,
     },
     ray::Ray,
 };
@@ -14,19 +15,21 @@ use crate::{
 #[derive(Debug)]
 pub enum Csg {
     Branch {
-        child1: Box<Csg>,
-        child2: Box<Csg>,
+        children: Vec<Csg>,
         operation: Op,
+        bounds: Option<Aabb>,
     },
     Leaf {
         object: Box<dyn Object + Send + Sync + 'static>,
+        bounds: Option<Aabb>,
     },
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Op {
     Union,
     Intersection,
+    // the first child with all the others taken away
     Difference,
 }
 
@@ -36,23 +39,93 @@ impl Csg {
         T1: Into<Csg>,
         T2: Into<Csg>,
     {
+        Self::new_nary(child1, [child2], operation)
+    }
+
+    // the first child is taken apart from the rest so a branch can never be empty
+    pub fn new_nary<T, I>(first: T, rest: I, operation: Op) -> Self
+    where
+        T: Into<Csg>,
+        I: IntoIterator,
+        I::Item: Into<Csg>,
+    {
+        let mut children = vec![first.into()];
+        children.extend(rest.into_iter().map(Into::into));
+        Self::from_children(children, operation)
+    }
+
+    fn from_children(children: Vec<Csg>, operation: Op) -> Self {
+        let bounds = operation.bounds(&children);
         Csg::Branch {
-            child1: Box::new(child1.into()),
-            child2: Box::new(child2.into()),
+            children,
             operation,
+            bounds,
         }
     }
 
     pub fn new_leaf(object: Box<dyn Object + Send + Sync + 'static>) -> Self {
-        Csg::Leaf { object }
+        let bounds = object.bounds();
+        Csg::Leaf { object, bounds }
+    }
+
+    fn join(self, other: Csg, operation: Op) -> Self {
+        // keep adding to a branch of the same operation rather than nesting a new one
+        let mut children = match self {
+            Csg::Branch {
+                children,
+                operation: op,
+                ..
+            } if op == operation => children,
+            first => vec![first],
+        };
+        match other {
+            // a difference takes away its second object, so that can't be flattened
+            Csg::Branch {
+                children: more,
+                operation: op,
+                ..
+            } if op == operation && op != Op::Difference => children.extend(more),
+            other => children.push(other),
+        }
+        Self::from_children(children, operation)
     }
 }
 
+// fluent construction of csg trees, as in a.union(b).minus(c)
+pub trait CsgBuilder: Into<Csg> {
+    fn union<T: Into<Csg>>(self, other: T) -> Csg {
+        self.into().join(other.into(), Op::Union)
+    }
+
+    fn intersect<T: Into<Csg>>(self, other: T) -> Csg {
+        self.into().join(other.into(), Op::Intersection)
+    }
+
+    fn minus<T: Into<Csg>>(self, other: T) -> Csg {
+        self.into().join(other.into(), Op::Difference)
+    }
+}
+
+impl<T: Into<Csg>> CsgBuilder for T {}
+
 pub trait FromCsg: Object {}
+impl FromCsg for AxisBox {}
+impl FromCsg for Bezier {}
+impl FromCsg for Cone {}
+impl FromCsg for Curves {}
+impl FromCsg for Cylinder {}
+impl FromCsg for Disk {}
+impl FromCsg for Heightfield {}
+impl FromCsg for Instance {}
+impl FromCsg for Metaballs {}
+impl FromCsg for Moving {}
 impl FromCsg for Plane {}
 impl FromCsg for PolyMesh {}
+impl FromCsg for Quad {}
 impl FromCsg for Quadratic {}
+impl FromCsg for Sdf {}
 impl FromCsg for Sphere {}
+impl FromCsg for Torus {}
 impl FromCsg for Triangle {}
 
 impl<T> From<T> for Csg
@@ -64,159 +137,189 @@ where
     }
 }
 
-impl Object for Csg {
-    fn intersection(&self, ray: &Ray) -> Vec<Hit> {
+impl Csg {
+    // the hits along the ray, and whether the ray is inside all along when there are none
+    fn trace(&self, ray: &Ray) -> (Vec<Hit<'_>>, bool) {
+        // children whose bounds the ray misses can't be hit at all
+        if self.bounds().is_some_and(|b| b.hit_by(ray).is_none()) {
+            return (Vec::new(), false);
+        }
         match self {
             Csg::Branch {
-                child1,
-                child2,
+                children,
                 operation,
+                ..
             } => {
-                let int_a = VecDeque::from(child1.intersection(ray));
-                let int_b = VecDeque::from(child2.intersection(ray));
-
-                // perform operation to merge intersections
-                match operation {
-                    Op::Union => Op::union(int_a, int_b),
-                    Op::Intersection => Op::intersection(int_a, int_b),
-                    Op::Difference => Op::difference(int_a, int_b),
-                }
+                // merge intersections one child at a time
+                let mut children = children.iter();
+                let first = children
+                    .next()
+                    .map_or((Vec::new(), false), |c| c.trace(ray));
+                children.fold(first, |result, child| {
+                    if result.0.is_empty() && !result.1 && *operation != Op::Union {
+                        return result; // nothing left to intersect or take away from
+                    }
+                    operation.combine(result, child.trace(ray))
+                })
+            }
+            Csg::Leaf { object, .. } => {
+                let hits = object.intersection(ray);
+                let enclosed = hits.is_empty() && object.encloses(ray);
+                (hits, enclosed)
             }
-            Csg::Leaf { object } => object.intersection(ray),
         }
     }
+}
+
+impl Object for Csg {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        self.trace(ray).0
+    }
 
     fn apply_transform(&mut self, t: Affine3A) {
         match self {
-            Csg::Branch { child1, child2, .. } => {
-                child1.apply_transform(t);
-                child2.apply_transform(t);
+            Csg::Branch {
+                children,
+                operation,
+                bounds,
+            } => {
+                for child in children.iter_mut() {
+                    child.apply_transform(t);
+                }
+                *bounds = operation.bounds(children);
+            }
+            Csg::Leaf { object, bounds } => {
+                object.apply_transform(t);
+                *bounds = object.bounds();
             }
-            Csg::Leaf { object } => object.apply_transform(t),
         }
     }
+
+    fn bounds(&self) -> Option<Aabb> {
+        match self {
+            Csg::Branch { bounds, .. } | Csg::Leaf { bounds, .. } => *bounds,
+        }
+    }
+
+    fn encloses(&self, ray: &Ray) -> bool {
+        self.trace(ray).1
+    }
 }
 
 impl Op {
-    pub fn union<'a>(mut int_a: VecDeque<Hit<'a>>, mut int_b: VecDeque<Hit<'a>>) -> Vec<Hit<'a>> {
-        use Ordering::*;
-        let mut int_result = Vec::new();
-        let mut inside_a = false;
-        let mut inside_b = false;
-        while !(int_a.is_empty() || int_b.is_empty()) {
-            match (
-                inside_a,
-                inside_b,
-                int_a[0].t.partial_cmp(&int_b[0].t).unwrap(),
-            ) {
-                (false, false, Less) => keep(&mut int_a, &mut int_result, &mut inside_a),
-                (false, false, Greater) => keep(&mut int_b, &mut int_result, &mut inside_b),
-                (false, false, Equal) => keep(&mut int_a, &mut int_result, &mut inside_a),
-
-                (true, false, Less) => keep(&mut int_a, &mut int_result, &mut inside_a),
-                (true, false, Greater) => discard(&mut int_b, &mut inside_b),
-                (true, false, Equal) => discard(&mut int_a, &mut inside_a),
-
-                (true, true, Less) => discard(&mut int_a, &mut inside_a),
-                (true, true, Greater) => discard(&mut int_b, &mut inside_b),
-                (true, true, Equal) => discard(&mut int_a, &mut inside_a),
-
-                (false, true, Less) => discard(&mut int_a, &mut inside_a),
-                (false, true, Greater) => keep(&mut int_b, &mut int_result, &mut inside_b),
-                (false, true, Equal) => discard(&mut int_b, &mut inside_b),
-            }
-        }
-        while !int_a.is_empty() {
-            keep(&mut int_a, &mut int_result, &mut inside_a);
+    fn bounds(&self, children: &[Csg]) -> Option<Aabb> {
+        let mut bounds = children.iter().map(|c| c.bounds());
+        match self {
+            // unbounded if any child is
+            Op::Union => bounds.try_fold(Aabb::default(), |b, c| Some(b.union(&c?))),
+            // only as large as the smallest bounded child
+            Op::Intersection => bounds.flatten().reduce(|a, b| a.intersect(&b)),
+            Op::Difference => bounds.next().flatten(),
         }
-        while !int_b.is_empty() {
-            keep(&mut int_b, &mut int_result, &mut inside_b);
+    }
+
+    fn contains(&self, inside_a: bool, inside_b: bool) -> bool {
+        match self {
+            Op::Union => inside_a || inside_b,
+            Op::Intersection => inside_a && inside_b,
+            Op::Difference => inside_a && !inside_b,
         }
-        int_result
     }
-    pub fn intersection<'a>(
-        mut int_a: VecDeque<Hit<'a>>,
-        mut int_b: VecDeque<Hit<'a>>,
-    ) -> Vec<Hit<'a>> {
-        use Ordering::*;
+
+    // combines the hits of two children, each with whether the ray is inside that child all
+    // along when it has no hits, giving the same for the result
+    pub fn combine<'a>(
+        &self,
+        (mut int_a, enclosed_a): (Vec<Hit<'a>>, bool),
+        (mut int_b, enclosed_b): (Vec<Hit<'a>>, bool),
+    ) -> (Vec<Hit<'a>>, bool) {
+        int_a.sort_by(|a, b| a.t.total_cmp(&b.t));
+        int_b.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        // track how many surfaces deep the ray is from the hits' entering flags
+        let mut events = int_a
+            .iter()
+            .map(|h| (h, false))
+            .chain(int_b.iter().map(|h| (h, true)))
+            .collect::<Vec<_>>();
+        events.sort_by(|a, b| a.0.t.total_cmp(&b.0.t));
+
+        // a union shares one count between both children, so a closed surface split into
+        // pieces such as triangles still encloses a solid
+        let shared = matches!(self, Op::Union);
+        let (mut depth_a, mut depth_b) = if shared {
+            let enclosed = enclosed_a || enclosed_b;
+            (
+                starting_depth(events.iter().map(|e| e.0)) + enclosed as i32,
+                0,
+            )
+        } else {
+            (
+                starting_depth(int_a.iter()) + enclosed_a as i32,
+                starting_depth(int_b.iter()) + enclosed_b as i32,
+            )
+        };
+        // with no crossings in the result, the ray stays however it started
+        let enclosed = self.contains(depth_a > 0, depth_b > 0);
+
         let mut int_result = Vec::new();
-        let mut inside_a = false;
-        let mut inside_b = false;
-        while !(int_a.is_empty() || int_b.is_empty()) {
-            match (
-                inside_a,
-                inside_b,
-                int_a[0].t.partial_cmp(&int_b[0].t).unwrap(),
-            ) {
-                (false, false, Less) => discard(&mut int_a, &mut inside_a),
-                (false, false, Greater) => discard(&mut int_b, &mut inside_b),
-                (false, false, Equal) => keep(&mut int_a, &mut int_result, &mut inside_a),
-
-                (true, false, Less) => discard(&mut int_a, &mut inside_a),
-                (true, false, Greater) => keep(&mut int_b, &mut int_result, &mut inside_b),
-                (true, false, Equal) => discard(&mut int_a, &mut inside_a),
-
-                (true, true, Less) => keep(&mut int_a, &mut int_result, &mut inside_a),
-                (true, true, Greater) => keep(&mut int_b, &mut int_result, &mut inside_b),
-                (true, true, Equal) => keep(&mut int_a, &mut int_result, &mut inside_a),
-
-                (false, true, Less) => keep(&mut int_a, &mut int_result, &mut inside_a),
-                (false, true, Greater) => discard(&mut int_b, &mut inside_b),
-                (false, true, Equal) => discard(&mut int_b, &mut inside_b),
+        let mut i = 0;
+        while i < events.len() {
+            // hits at the same distance are applied together so touching surfaces don't add
+            // a zero length gap
+            let before = self.contains(depth_a > 0, depth_b > 0);
+            let start = i;
+            while i < events.len() && events[i].0.t - events[start].0.t < 0.000001 {
+                let (hit, from_b) = events[i];
+                let change = if hit.entering { 1 } else { -1 };
+                if from_b && !shared {
+                    depth_b += change;
+                } else {
+                    depth_a += change;
+                }
+                i += 1;
             }
-        }
-        drop(int_a);
-        drop(int_b);
-        int_result
-    }
-    pub fn difference<'a>(
-        mut int_a: VecDeque<Hit<'a>>,
-        mut int_b: VecDeque<Hit<'a>>,
-    ) -> Vec<Hit<'a>> {
-        use Ordering::*;
-        let mut int_result = Vec::new();
-        let mut inside_a = false;
-        let mut inside_b = false;
-        while !(int_a.is_empty() || int_b.is_empty()) {
-            match (
-                inside_a,
-                inside_b,
-                int_a[0].t.partial_cmp(&int_b[0].t).unwrap(),
-            ) {
-                (false, false, Less) => keep(&mut int_a, &mut int_result, &mut inside_a),
-                (false, false, Greater) => discard(&mut int_b, &mut inside_b),
-                (false, false, Equal) => discard(&mut int_b, &mut inside_b),
-
-                (true, false, Less) => keep(&mut int_a, &mut int_result, &mut inside_a),
-                (true, false, Greater) => keep(&mut int_b, &mut int_result, &mut inside_b),
-                (true, false, Equal) => keep(&mut int_a, &mut int_result, &mut inside_a),
-
-                (true, true, Less) => discard(&mut int_a, &mut inside_a),
-                (true, true, Greater) => keep(&mut int_b, &mut int_result, &mut inside_b),
-                (true, true, Equal) => discard(&mut int_a, &mut inside_a),
-
-                (false, true, Less) => discard(&mut int_a, &mut inside_a),
-                (false, true, Greater) => discard(&mut int_b, &mut inside_b),
-                (false, true, Equal) => discard(&mut int_b, &mut inside_b),
+            let after = self.contains(depth_a > 0, depth_b > 0);
+            if before == after {
+                continue;
             }
+
+            // prefer a hit that crosses in the same direction as the result
+            let (hit, from_b) = events[start..i]
+                .iter()
+                .copied()
+                .find(|(h, from_b)| {
+                    h.entering == (after != (*from_b && matches!(self, Op::Difference)))
+                })
+                .unwrap_or(events[start]);
+            let mut outward = if hit.entering {
+                hit.normal
+            } else {
+                -hit.normal
+            };
+            if from_b && matches!(self, Op::Difference) {
+                // surfaces carved out by the second object face into it
+                outward = -outward;
+            }
+            int_result.push(Hit {
+                entering: after,
+                normal: if after { outward } else { -outward },
+                ..hit.clone()
+            });
         }
-        while !int_a.is_empty() {
-            keep(&mut int_a, &mut int_result, &mut inside_a);
-        }
-        drop(int_b);
-        int_result
+        let enclosed = int_result.is_empty() && enclosed;
+        (int_result, enclosed)
     }
 }
 
-fn keep<'a>(source: &mut VecDeque<Hit<'a>>, dest: &mut Vec<Hit<'a>>, inside: &mut bool) {
-    // keep the remaining intersection points in source
-    *inside = !*inside;
-    let val = source.pop_front().unwrap();
-    dest.push(val);
-}
-
-fn discard(source: &mut VecDeque<Hit>, inside: &mut bool) {
-    *inside = !*inside;
-    // pop and discard unneeded intersection points with _
-    let _ = source.pop_front().unwrap();
+fn starting_depth<'a, 'b: 'a>(hits: impl Iterator<Item = &'a Hit<'b>>) -> i32 {
+    // start from whatever depth keeps the count from going negative, so rays that begin
+    // inside an object, or only cross one side of a plane, count as inside until they leave
+    let mut depth = 0;
+    let mut lowest = 0;
+    for hit in hits {
+        depth += if hit.entering { 1 } else { -1 };
+        lowest = lowest.min(depth);
+    }
+    -lowest
 }
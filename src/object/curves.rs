@@ -0,0 +1,334 @@
+use std::f32::consts::SQRT_2;
+
+use glam::{Affine3A, Vec2, Vec3A};
+
+use super::{
+    bezier::{bernstein, split_curve},
+    Object,
+};
+use crate::{
+    aabb::Aabb,
+    hit::Hit,
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+// the most curves kept in one leaf of the hierarchy
+const LEAF_SIZE: usize = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CurveShape {
+    // flat strips that always face the ray, cheap enough for fur and grass
+    Ribbon,
+    // round tubes, for thicker fibres seen up close
+    Cylinder,
+}
+
+// a cubic bézier segment with a width at each control point
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Curve {
+    pub points: [Vertex; 4],
+    pub widths: [f32; 4],
+}
+
+impl Curve {
+    pub fn new(points: [Vertex; 4], widths: [f32; 4]) -> Self {
+        Self { points, widths }
+    }
+
+    pub fn from_b_spline(points: &[Vertex], widths: &[f32]) -> Vec<Self> {
+        // convert each span of a uniform cubic b-spline to its equivalent bézier segment
+        if points.len() != widths.len() {
+            panic!(
+                "B-spline has {} points but {} widths",
+                points.len(),
+                widths.len()
+            );
+        }
+        points
+            .windows(4)
+            .zip(widths.windows(4))
+            .map(|(p, w)| {
+                Self::new(
+                    [
+                        (p[0] + 4. * p[1] + p[2]) / 6.,
+                        (2. * p[1] + p[2]) / 3.,
+                        (p[1] + 2. * p[2]) / 3.,
+                        (p[1] + 4. * p[2] + p[3]) / 6.,
+                    ],
+                    [
+                        (w[0] + 4. * w[1] + w[2]) / 6.,
+                        (2. * w[1] + w[2]) / 3.,
+                        (w[1] + 2. * w[2]) / 3.,
+                        (w[1] + 4. * w[2] + w[3]) / 6.,
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    fn bounds(&self) -> Aabb {
+        // the curve lies within its control points, widened by the thickest point
+        let half_width = self.widths.iter().copied().fold(0., f32::max) / 2.;
+        Aabb::from_points(&self.points).pad(half_width)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Branch {
+        bounds: Aabb,
+        left: usize,
+        right: usize,
+    },
+    Leaf {
+        bounds: Aabb,
+        start: usize,
+        end: usize,
+    },
+}
+
+#[derive(Debug)]
+pub struct Curves {
+    curves: Vec<Curve>,
+    nodes: Vec<Node>,
+    shape: CurveShape,
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Curves {
+    pub fn new<M>(mut curves: Vec<Curve>, shape: CurveShape, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        let mut nodes = Vec::new();
+        if !curves.is_empty() {
+            let count = curves.len();
+            build(&mut curves, 0, count, &mut nodes);
+        }
+        Self {
+            curves,
+            nodes,
+            shape,
+            transform: Transform::default(),
+            material: Box::new(material),
+        }
+    }
+
+    fn intersect_curve(&self, curve: &Curve, ray: &Ray, hits: &mut Vec<LocalHit>) {
+        // move into a space where the ray starts at the origin and runs along z
+        let length = ray.direction.length();
+        let z = ray.direction / length;
+        let (x, y) = z.any_orthonormal_pair();
+        let to_ray = |p: Vertex| {
+            let p = p - ray.position;
+            Vec3A::new(p.dot(x), p.dot(y), p.dot(z))
+        };
+        let from_ray = |v: Vec3A| v.x * x + v.y * y + v.z * z;
+        let points = curve.points.map(to_ray);
+
+        // subdivide until each piece is close enough to a straight line
+        let width = curve.widths.iter().copied().fold(0., f32::max);
+        let bend = (0..2)
+            .map(|i| (points[i] - 2. * points[i + 1] + points[i + 2]).length())
+            .fold(0., f32::max);
+        let epsilon = width / 20.;
+        let depth = ((SQRT_2 * 6. * bend / (8. * epsilon)).max(1.).log2() / 2.).round() as usize;
+
+        let mut found = Vec::new();
+        self.subdivide(
+            curve,
+            &points,
+            points,
+            (0., 1.),
+            depth.min(10),
+            width / 2.,
+            &mut found,
+        );
+        // neighbouring pieces both accept a ray through the point they share
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        found.dedup_by(|a, b| (a.0 - b.0).abs() < 0.0001 && (a.3 - b.3).length() < 0.001);
+        for (t, normal, tangent, uv) in found {
+            hits.push(LocalHit {
+                t: t / length,
+                normal: from_ray(normal),
+                tangent: from_ray(tangent),
+                uv,
+            });
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn subdivide(
+        &self,
+        curve: &Curve,
+        whole: &[Vertex; 4],
+        points: [Vertex; 4],
+        u: (f32, f32),
+        depth: usize,
+        half_width: f32,
+        found: &mut Vec<(f32, Vec3A, Vec3A, Vec2)>,
+    ) {
+        // skip pieces whose widened bounds miss the ray
+        let bounds = Aabb::from_points(&points).pad(half_width);
+        if bounds.min.x > 0. || bounds.max.x < 0. || bounds.min.y > 0. || bounds.max.y < 0. {
+            return;
+        }
+
+        if depth > 0 {
+            let mid = (u.0 + u.1) / 2.;
+            let (low, high) = split_curve(points);
+            self.subdivide(curve, whole, low, (u.0, mid), depth - 1, half_width, found);
+            self.subdivide(curve, whole, high, (mid, u.1), depth - 1, half_width, found);
+            return;
+        }
+
+        // treat the piece as a line and reject rays passing beyond either end
+        let start =
+            (points[1].y - points[0].y) * -points[0].y + points[0].x * (points[0].x - points[1].x);
+        let end =
+            (points[2].y - points[3].y) * -points[3].y + points[3].x * (points[3].x - points[2].x);
+        if start < 0. || end < 0. {
+            return;
+        }
+        let segment = Vec2::new(points[3].x - points[0].x, points[3].y - points[0].y);
+        let w = (-Vec2::new(points[0].x, points[0].y).dot(segment) / segment.length_squared())
+            .clamp(0., 1.);
+        let u = u.0 + w * (u.1 - u.0);
+
+        // the point and width on the full curve
+        let (b, db) = bernstein(u);
+        let centre = (0..4).fold(Vec3A::ZERO, |sum, i| sum + whole[i] * b[i]);
+        let tangent = (0..4)
+            .fold(Vec3A::ZERO, |sum, i| sum + whole[i] * db[i])
+            .try_normalize()
+            .unwrap_or(Vec3A::X);
+        let radius = (0..4).map(|i| curve.widths[i] * b[i]).sum::<f32>() / 2.;
+        let offset = Vec2::new(centre.x, centre.y);
+        if offset.length_squared() > radius * radius {
+            return;
+        }
+
+        // v runs across the curve from one edge to the other
+        let side = Vec2::new(tangent.x, tangent.y).perp_dot(-offset).signum();
+        let uv = Vec2::new(u, 0.5 + side * offset.length() / (2. * radius));
+        let across = |v: Vec3A| v - v.dot(tangent) * tangent;
+        match self.shape {
+            CurveShape::Ribbon => {
+                // facing back along the ray, but kept perpendicular to the curve
+                let normal = across(-Vec3A::Z).try_normalize().unwrap_or(-Vec3A::Z);
+                found.push((centre.z, normal, tangent, uv));
+            }
+            CurveShape::Cylinder => {
+                // intersect the tube around the tangent line at the closest point
+                let m = across(Vec3A::Z);
+                let n = across(-centre);
+                let a = m.dot(m);
+                let b = 2. * m.dot(n);
+                let c = n.dot(n) - radius * radius;
+                let disc = b * b - 4. * a * c;
+                if a == 0. || disc < 0. {
+                    return;
+                }
+                for t in [(-b - disc.sqrt()) / (2. * a), (-b + disc.sqrt()) / (2. * a)] {
+                    let normal = across(Vec3A::new(0., 0., t) - centre)
+                        .try_normalize()
+                        .unwrap_or(-Vec3A::Z);
+                    found.push((t, normal, tangent, uv));
+                }
+            }
+        }
+    }
+}
+
+impl Object for Curves {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let local = self.transform.ray_to_local(ray);
+        let mut hits = Vec::new();
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(i) = stack.pop() {
+            match self.nodes[i] {
+                Node::Branch {
+                    bounds,
+                    left,
+                    right,
+                } => {
+                    if bounds.hit_by(&local).is_some() {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+                Node::Leaf { bounds, start, end } => {
+                    if bounds.hit_by(&local).is_some() {
+                        for curve in &self.curves[start..end] {
+                            self.intersect_curve(curve, &local, &mut hits);
+                        }
+                    }
+                }
+            }
+        }
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let local = self
+            .curves
+            .iter()
+            .fold(Aabb::default(), |b, c| b.union(&c.bounds()));
+        Some(local.transformed(self.transform.to_world()))
+    }
+}
+
+fn build(curves: &mut [Curve], start: usize, end: usize, nodes: &mut Vec<Node>) -> usize {
+    // split at the median along the longest axis of the curves' centres
+    let bounds = curves[start..end]
+        .iter()
+        .fold(Aabb::default(), |b, c| b.union(&c.bounds()));
+    let index = nodes.len();
+    if end - start <= LEAF_SIZE {
+        nodes.push(Node::Leaf { bounds, start, end });
+        return index;
+    }
+
+    let centres = Aabb::from_points(
+        &curves[start..end]
+            .iter()
+            .map(|c| c.bounds().centroid())
+            .collect::<Vec<_>>(),
+    );
+    let size = centres.size();
+    let axis = if size.x >= size.y && size.x >= size.z {
+        0
+    } else if size.y >= size.z {
+        1
+    } else {
+        2
+    };
+    let mid = (start + end) / 2;
+    curves[start..end].select_nth_unstable_by(mid - start, |a, b| {
+        a.bounds().centroid()[axis].total_cmp(&b.bounds().centroid()[axis])
+    });
+
+    // reserve this node's slot before building the children after it
+    nodes.push(Node::Leaf { bounds, start, end });
+    let left = build(curves, start, mid, nodes);
+    let right = build(curves, mid, end, nodes);
+    nodes[index] = Node::Branch {
+        bounds,
+        left,
+        right,
+    };
+    index
+}
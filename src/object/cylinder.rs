@@ -0,0 +1,104 @@
+use std::f32::consts::PI;
+
+use glam::{Affine3A, Quat, Vec2, Vec3, Vec3A};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::{tangent_around_y, Hit},
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+#[derive(Debug)]
+pub struct Cylinder {
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Cylinder {
+    // a capped cylinder running from the base along the axis, which also sets its height
+    pub fn new<M>(base: Vertex, axis: Vec3A, radius: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            transform: Transform::new(axis_frame(base, axis, radius)),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Object for Cylinder {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        // local cylinder has radius 1 and runs from y = 0 to y = 1
+        let local = self.transform.ray_to_local(ray);
+        let (p, d) = (local.position, local.direction);
+        let mut hits = Vec::new();
+
+        let a = d.x.powi(2) + d.z.powi(2);
+        let b = 2. * (p.x * d.x + p.z * d.z);
+        let c = p.x.powi(2) + p.z.powi(2) - 1.;
+        let disc = b.powi(2) - 4. * a * c;
+        if a > 0. && disc >= 0. {
+            let ds = disc.sqrt();
+            for t in [(-b - ds) / (2. * a), (-b + ds) / (2. * a)] {
+                let q = p + t * d;
+                if (0. ..=1.).contains(&q.y) {
+                    let normal = Vec3A::new(q.x, 0., q.z);
+                    hits.push(LocalHit {
+                        t,
+                        normal,
+                        tangent: tangent_around_y(normal),
+                        uv: Vec2::new(0.5 + q.z.atan2(q.x) / (2. * PI), q.y),
+                    });
+                }
+            }
+        }
+
+        hits.extend(caps(p, d, &[(0., -1.), (1., 1.)]));
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let local = Aabb::new(Vec3A::new(-1., 0., -1.), Vec3A::ONE);
+        Some(local.transformed(self.transform.to_world()))
+    }
+}
+
+pub(crate) fn axis_frame(base: Vertex, axis: Vec3A, radius: f32) -> Affine3A {
+    // maps the local y axis onto the given axis, scaling x and z by the radius
+    Affine3A::from_scale_rotation_translation(
+        Vec3::new(radius, axis.length(), radius),
+        Quat::from_rotation_arc(Vec3::Y, axis.normalize().into()),
+        base.into(),
+    )
+}
+
+pub(crate) fn caps(p: Vec3A, d: Vec3A, caps: &[(f32, f32)]) -> Vec<LocalHit> {
+    // unit disks at the given heights, facing up or down the y axis
+    let mut hits = Vec::new();
+    if d.y == 0. {
+        return hits;
+    }
+    for &(height, facing) in caps {
+        let t = (height - p.y) / d.y;
+        let q = p + t * d;
+        if q.x.powi(2) + q.z.powi(2) <= 1. {
+            hits.push(LocalHit {
+                t,
+                normal: Vec3A::new(0., facing, 0.),
+                tangent: Vec3A::X,
+                uv: Vec2::new((q.x + 1.) / 2., (q.z + 1.) / 2.),
+            });
+        }
+    }
+    hits
+}
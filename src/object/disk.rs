@@ -0,0 +1,73 @@
+use std::f32::consts::PI;
+
+use glam::{Affine3A, Quat, Vec2, Vec3, Vec3A};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::Hit,
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+#[derive(Debug)]
+pub struct Disk {
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Disk {
+    pub fn new<M>(center: Vertex, normal: Vec3A, radius: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // a unit disk in the local xz plane, facing along y
+        let rotation = Quat::from_rotation_arc(Vec3::Y, normal.normalize().into());
+        Self {
+            transform: Transform::new(Affine3A::from_scale_rotation_translation(
+                Vec3::splat(radius),
+                rotation,
+                center.into(),
+            )),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Object for Disk {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let epsilon = 0.0000001;
+        let local = self.transform.ray_to_local(ray);
+        if local.direction.y > -epsilon && local.direction.y < epsilon {
+            return Vec::new(); // ray parallel to disk
+        }
+
+        let t = -local.position.y / local.direction.y;
+        let p = local.position + t * local.direction;
+        let r = (p.x.powi(2) + p.z.powi(2)).sqrt();
+        if r > 1. {
+            return Vec::new();
+        }
+
+        let hit = LocalHit {
+            t,
+            normal: Vec3A::Y,
+            tangent: Vec3A::X,
+            // distance from the centre and angle around it
+            uv: Vec2::new(r, 0.5 + p.z.atan2(p.x) / (2. * PI)),
+        };
+        self.transform
+            .hits_to_world(ray, vec![hit], self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let local = Aabb::new(Vec3A::new(-1., 0., -1.), Vec3A::new(1., 0., 1.));
+        Some(local.transformed(self.transform.to_world()))
+    }
+}
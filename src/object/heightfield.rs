@@ -0,0 +1,245 @@
+use std::fs::File;
+
+use glam::{Affine3A, Vec2, Vec3, Vec3A};
+use png::{BitDepth, Decoder, Transformations};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::Hit,
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+#[derive(Debug)]
+pub struct Heightfield {
+    size: [usize; 2],
+    heights: Vec<f32>,
+    normals: Vec<Vec3A>,
+    range: (f32, f32),
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Heightfield {
+    // heights are read from the first channel of a png, with black at min.y and white at max.y
+    pub fn new<M>(filename: &str, min: Vertex, max: Vertex, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        let mut decoder = Decoder::new(File::open(filename).expect("Should read the file"));
+        // expand palettes and low bit depths so every sample is 8 or 16 bits
+        decoder.set_transformations(Transformations::EXPAND);
+        let mut reader = decoder.read_info().expect("Should be a png file");
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).expect("Should decode the png");
+        let bytes = &buf[..info.buffer_size()];
+
+        let channels = info.color_type.samples();
+        let heights = match info.bit_depth {
+            BitDepth::Sixteen => bytes
+                .chunks_exact(2 * channels)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]) as f32 / 65535.)
+                .collect(),
+            _ => bytes
+                .chunks_exact(channels)
+                .map(|c| c[0] as f32 / 255.)
+                .collect(),
+        };
+        Self::from_values(
+            [info.width as usize, info.height as usize],
+            heights,
+            min,
+            max,
+            material,
+        )
+    }
+
+    // a grid of heights from 0 to 1 with x varying fastest, stretched over the extents
+    pub fn from_values<M>(
+        size: [usize; 2],
+        heights: Vec<f32>,
+        min: Vertex,
+        max: Vertex,
+        material: M,
+    ) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        if size[0] < 2 || size[1] < 2 {
+            panic!(
+                "Heightfield needs at least 2 by 2 samples, found {:?}",
+                size
+            );
+        }
+        if heights.len() != size[0] * size[1] {
+            panic!(
+                "Heightfield of size {:?} needs {} values, found {}",
+                size,
+                size[0] * size[1],
+                heights.len()
+            );
+        }
+        let range = heights
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| {
+                (lo.min(h), hi.max(h))
+            });
+
+        // in local space each grid cell is one unit square, with samples at integer x and z
+        let extent = max - min;
+        let transform = Transform::new(Affine3A::from_scale_rotation_translation(
+            Vec3::new(
+                extent.x / (size[0] - 1) as f32,
+                extent.y,
+                extent.z / (size[1] - 1) as f32,
+            ),
+            Default::default(),
+            min.into(),
+        ));
+
+        let mut field = Self {
+            size,
+            heights,
+            normals: Vec::new(),
+            range,
+            transform,
+            material: Box::new(material),
+        };
+        // vertex normals from the central differences of the neighbouring heights
+        field.normals = (0..size[1])
+            .flat_map(|z| (0..size[0]).map(move |x| (x, z)))
+            .map(|(x, z)| {
+                let dx = field.height(x + 1, z) - field.height(x.saturating_sub(1), z);
+                let dz = field.height(x, z + 1) - field.height(x, z.saturating_sub(1));
+                let span = |c: usize, n: usize| ((c + 1).min(n - 1) - c.saturating_sub(1)) as f32;
+                Vec3A::new(-dx / span(x, size[0]), 1., -dz / span(z, size[1])).normalize()
+            })
+            .collect();
+        field
+    }
+
+    fn index(&self, x: usize, z: usize) -> usize {
+        z.min(self.size[1] - 1) * self.size[0] + x.min(self.size[0] - 1)
+    }
+
+    fn height(&self, x: usize, z: usize) -> f32 {
+        self.heights[self.index(x, z)]
+    }
+
+    fn intersect_cell(&self, ray: &Ray, x: usize, z: usize, hits: &mut Vec<LocalHit>) {
+        // each cell is split into two triangles along its diagonal
+        let corner = |dx: usize, dz: usize| {
+            let (cx, cz) = (x + dx, z + dz);
+            (
+                Vertex::new(cx as f32, self.height(cx, cz), cz as f32),
+                self.normals[self.index(cx, cz)],
+            )
+        };
+        let (c00, c10, c11, c01) = (corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1));
+        for [a, b, c] in [[c00, c11, c10], [c00, c01, c11]] {
+            // the MT algorithm as used for triangles
+            let e1 = b.0 - a.0;
+            let e2 = c.0 - a.0;
+            let h = ray.direction.cross(e2);
+            let det = e1.dot(h);
+            if det.abs() < 0.0000001 {
+                continue; // ray parallel to triangle
+            }
+            let f = 1. / det;
+            let s = ray.position - a.0;
+            let u = f * s.dot(h);
+            let q = s.cross(e1);
+            let v = f * ray.direction.dot(q);
+            if u < 0. || v < 0. || u + v > 1. {
+                continue;
+            }
+
+            let t = f * e2.dot(q);
+            let p = ray.position + t * ray.direction;
+            hits.push(LocalHit {
+                t,
+                normal: a.1 * (1. - u - v) + b.1 * u + c.1 * v,
+                tangent: Vec3A::X,
+                uv: Vec2::new(
+                    p.x / (self.size[0] - 1) as f32,
+                    p.z / (self.size[1] - 1) as f32,
+                ),
+            });
+        }
+    }
+}
+
+impl Object for Heightfield {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let local = self.transform.ray_to_local(ray);
+        let (p, d) = (local.position, local.direction);
+        let cells = [self.size[0] - 1, self.size[1] - 1];
+
+        // clip the ray to the bounding box of the grid
+        let lower = Vec3A::new(0., self.range.0, 0.);
+        let upper = Vec3A::new(cells[0] as f32, self.range.1, cells[1] as f32);
+        let (mut t_enter, mut t_exit) = (f32::NEG_INFINITY, f32::INFINITY);
+        for axis in 0..3 {
+            let inv = 1. / d[axis];
+            let t0 = (lower[axis] - p[axis]) * inv;
+            let t1 = (upper[axis] - p[axis]) * inv;
+            if t0.is_nan() || t1.is_nan() {
+                continue; // ray lies exactly on a slab boundary
+            }
+            t_enter = t_enter.max(t0.min(t1));
+            t_exit = t_exit.min(t0.max(t1));
+        }
+        if t_enter > t_exit || t_enter.is_infinite() || t_exit.is_infinite() {
+            return Vec::new();
+        }
+
+        // walk the cells the ray passes over in order, a 2D digital differential analyser
+        let start = p + t_enter * d;
+        let start = [(start.x, 0), (start.z, 1)];
+        let mut cell = start.map(|(v, i)| (v.floor().max(0.) as usize).min(cells[i] - 1));
+        let (origin, direction) = ([p.x, p.z], [d.x, d.z]);
+        let step = direction.map(|v| if v > 0. { 1 } else { -1 });
+        let delta = direction.map(|v| 1. / v.abs());
+        let mut next = [0, 1].map(|i| {
+            let boundary = cell[i] as f32 + if direction[i] > 0. { 1. } else { 0. };
+            if direction[i] == 0. {
+                f32::INFINITY
+            } else {
+                (boundary - origin[i]) / direction[i]
+            }
+        });
+
+        let mut hits = Vec::new();
+        loop {
+            self.intersect_cell(&local, cell[0], cell[1], &mut hits);
+            let i = if next[0] < next[1] { 0 } else { 1 };
+            if next[i] > t_exit {
+                break;
+            }
+            match cell[i].checked_add_signed(step[i]) {
+                Some(c) if c < cells[i] => cell[i] = c,
+                _ => break,
+            }
+            next[i] += delta[i];
+        }
+
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let cells = [self.size[0] - 1, self.size[1] - 1];
+        let local = Aabb::new(
+            Vec3A::new(0., self.range.0, 0.),
+            Vec3A::new(cells[0] as f32, self.range.1, cells[1] as f32),
+        );
+        Some(local.transformed(self.transform.to_world()))
+    }
+}
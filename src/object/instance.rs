@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use glam::Affine3A;
+
+use super::Object;
+use crate::{aabb::Aabb, hit::Hit, material::Material, ray::Ray, transform::Transform};
+
+// a placement of a shared object, so many copies only store their geometry once
+#[derive(Debug)]
+pub struct Instance {
+    object: Arc<dyn Object + Send + Sync>,
+    transform: Transform,
+    material: Option<Box<dyn Material + Send + Sync>>,
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn Object + Send + Sync>, transform: Affine3A) -> Self {
+        Self {
+            object,
+            transform: Transform::new(transform),
+            material: None,
+        }
+    }
+
+    // an instance drawn in a different material to the shared object
+    pub fn with_material<M>(
+        object: Arc<dyn Object + Send + Sync>,
+        transform: Affine3A,
+        material: M,
+    ) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            object,
+            transform: Transform::new(transform),
+            material: Some(Box::new(material)),
+        }
+    }
+}
+
+impl Object for Instance {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        // t is the same in both spaces since the local ray direction isn't normalised
+        let local = self.transform.ray_to_local(ray);
+        self.transform
+            .object_hits_to_world(ray, self.object.intersection(&local), self)
+            .into_iter()
+            .map(|hit| Hit {
+                material: self.material.as_deref().unwrap_or(hit.material),
+                ..hit
+            })
+            .collect()
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        // only this placement moves, the shared object is left alone
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let local = self.object.bounds()?;
+        Some(local.transformed(self.transform.to_world()))
+    }
+
+    fn encloses(&self, ray: &Ray) -> bool {
+        self.object.encloses(&self.transform.ray_to_local(ray))
+    }
+}
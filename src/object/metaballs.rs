@@ -0,0 +1,163 @@
+use glam::{Affine3A, Vec2};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::{tangent_around_y, Hit},
+    material::Material,
+    polynomial::solve_in_range,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+// a point source whose influence falls smoothly to zero at its radius
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Metaball {
+    pub centre: Vertex,
+    pub radius: f32,
+    pub weight: f32,
+}
+
+impl Metaball {
+    pub fn new(centre: Vertex, radius: f32, weight: f32) -> Self {
+        Self {
+            centre,
+            radius,
+            weight,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Metaballs {
+    balls: Vec<Metaball>,
+    threshold: f32,
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Metaballs {
+    // the surface is where the summed field equals the threshold, with the inside above it
+    pub fn new<M>(balls: Vec<Metaball>, threshold: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            balls,
+            threshold,
+            transform: Transform::default(),
+            material: Box::new(material),
+        }
+    }
+
+    fn gradient(&self, p: Vertex) -> Vertex {
+        // derivative of w (1 - r^2 / R^2)^3 summed over the balls in range
+        self.balls.iter().fold(Vertex::ZERO, |sum, ball| {
+            let offset = p - ball.centre;
+            let r2 = ball.radius * ball.radius;
+            let q = 1. - offset.length_squared() / r2;
+            if q <= 0. {
+                sum
+            } else {
+                sum - ball.weight * 6. * q * q / r2 * offset
+            }
+        })
+    }
+}
+
+impl Object for Metaballs {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let local = self.transform.ray_to_local(ray);
+        let (o, d) = (local.position.as_dvec3(), local.direction.as_dvec3());
+
+        // each ball's field along the ray is a polynomial in t of degree six, nonzero only
+        // while the ray is inside the ball
+        let mut spans = Vec::new();
+        for ball in &self.balls {
+            let r2 = (ball.radius * ball.radius) as f64;
+            let oc = o - ball.centre.as_dvec3();
+            let (a, b, c) = (d.dot(d), 2. * d.dot(oc), oc.dot(oc));
+            let disc = b * b - 4. * a * (c - r2);
+            if disc <= 0. {
+                continue;
+            }
+            let t0 = (-b - disc.sqrt()) / (2. * a);
+            let t1 = (-b + disc.sqrt()) / (2. * a);
+
+            // cube 1 - |o + t d - c|^2 / R^2, highest power first
+            let q = [-a / r2, -b / r2, 1. - c / r2];
+            let q2 = multiply(&q, &q);
+            let field = multiply(&q2, &q)
+                .into_iter()
+                .map(|x| x * ball.weight as f64)
+                .collect::<Vec<_>>();
+            spans.push((t0, t1, field));
+        }
+
+        // split the ray wherever a ball starts or stops contributing
+        let mut bounds = spans.iter().flat_map(|s| [s.0, s.1]).collect::<Vec<_>>();
+        bounds.sort_by(f64::total_cmp);
+        let mut ts = Vec::new();
+        for pair in bounds.windows(2) {
+            let (lo, hi) = (pair[0], pair[1]);
+            let mid = (lo + hi) / 2.;
+            let mut sum = vec![0.; 7];
+            for (_, _, field) in spans.iter().filter(|s| s.0 <= mid && mid <= s.1) {
+                for (s, f) in sum.iter_mut().zip(field) {
+                    *s += f;
+                }
+            }
+            sum[6] -= self.threshold as f64;
+            for t in solve_in_range(&sum, lo, hi) {
+                if ts.last().is_none_or(|&last| t - last > 1e-9) {
+                    ts.push(t);
+                }
+            }
+        }
+
+        let hits = ts
+            .into_iter()
+            .map(|t| {
+                let t = t as f32;
+                let p = local.position + t * local.direction;
+                // the field falls away outwards, so the outward normal opposes its gradient
+                let normal = (-self.gradient(p))
+                    .try_normalize()
+                    .unwrap_or(-local.direction);
+                LocalHit {
+                    t,
+                    normal,
+                    tangent: tangent_around_y(normal),
+                    uv: Vec2::ZERO,
+                }
+            })
+            .collect();
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let local = self.balls.iter().fold(Aabb::default(), |b, ball| {
+            b.union(&Aabb::new(
+                ball.centre - ball.radius,
+                ball.centre + ball.radius,
+            ))
+        });
+        Some(local.transformed(self.transform.to_world()))
+    }
+}
+
+fn multiply(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
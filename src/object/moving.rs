@@ -0,0 +1,113 @@
+use glam::{Affine3A, Quat, Vec3};
+
+use super::Object;
+use crate::{aabb::Aabb, hit::Hit, ray::Ray, transform::Transform};
+
+// an object that moves between two placements, so it blurs while the shutter is open
+#[derive(Debug)]
+pub struct Moving {
+    object: Box<dyn Object + Send + Sync>,
+    start: Affine3A,
+    end: Affine3A,
+    // the placements split into scale, rotation and translation for blending between them
+    start_parts: (Vec3, Quat, Vec3),
+    end_parts: (Vec3, Quat, Vec3),
+    start_time: f32,
+    end_time: f32,
+}
+
+impl Moving {
+    // placed by start at time 0 and by end at time 1, neither of which can be sheared
+    pub fn new<T>(object: T, start: Affine3A, end: Affine3A) -> Self
+    where
+        T: Object + Send + Sync + 'static,
+    {
+        Self::with_times(object, (0., start), (1., end))
+    }
+
+    // held still before the start time and after the end time
+    pub fn with_times<T>(
+        object: T,
+        (start_time, start): (f32, Affine3A),
+        (end_time, end): (f32, Affine3A),
+    ) -> Self
+    where
+        T: Object + Send + Sync + 'static,
+    {
+        Self {
+            object: Box::new(object),
+            start,
+            end,
+            start_parts: split(start),
+            end_parts: split(end),
+            start_time,
+            end_time,
+        }
+    }
+
+    fn transform_at(&self, time: f32) -> Transform {
+        let s = if self.end_time > self.start_time {
+            ((time - self.start_time) / (self.end_time - self.start_time)).clamp(0., 1.)
+        } else {
+            0.
+        };
+        // translations and scales move in a straight line and rotations turn at a steady rate
+        let (scale0, rotation0, translation0) = self.start_parts;
+        let (scale1, rotation1, translation1) = self.end_parts;
+        Transform::new(Affine3A::from_scale_rotation_translation(
+            scale0.lerp(scale1, s),
+            rotation0.slerp(rotation1, s),
+            translation0.lerp(translation1, s),
+        ))
+    }
+}
+
+impl Object for Moving {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        // t is the same in both spaces since the local ray direction isn't normalised
+        let transform = self.transform_at(ray.time);
+        let local = transform.ray_to_local(ray);
+        transform.object_hits_to_world(ray, self.object.intersection(&local), self)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.start = t * self.start;
+        self.end = t * self.end;
+        self.start_parts = split(self.start);
+        self.end_parts = split(self.end);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        // points sweep along arcs while rotating, so only objects that keep their rotation
+        // stay within the boxes at either end
+        let (_, rotation0, _) = self.start_parts;
+        let (_, rotation1, _) = self.end_parts;
+        if !rotation0.abs_diff_eq(rotation1, 0.000001) {
+            return None;
+        }
+        let local = self.object.bounds()?;
+        Some(
+            local
+                .transformed(self.start)
+                .union(&local.transformed(self.end)),
+        )
+    }
+
+    fn encloses(&self, ray: &Ray) -> bool {
+        let local = self.transform_at(ray.time).ray_to_local(ray);
+        self.object.encloses(&local)
+    }
+}
+
+fn split(t: Affine3A) -> (Vec3, Quat, Vec3) {
+    // sheared placements can't be blended this way, so they aren't allowed
+    let parts = t.to_scale_rotation_translation();
+    let rebuilt = Affine3A::from_scale_rotation_translation(parts.0, parts.1, parts.2);
+    if !rebuilt
+        .matrix3
+        .abs_diff_eq(t.matrix3, 0.0001 * parts.0.abs().max_element())
+    {
+        panic!("Moving objects can't be sheared, found {:?}", t);
+    }
+    parts
+}
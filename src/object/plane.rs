@@ -1,11 +1,7 @@
-use glam::{Affine3A, Vec3A};
+use glam::{Affine3A, Vec2, Vec3A};
 
 use crate::{
-    hit::Hit,
-    material::{normalshading::NormalShading, Material},
-    object::Object,
-    ray::Ray,
-    Vertex,
+    hit::Hit, material::Material, object::Object, ray::Ray, transform::normal_matrix, Vertex,
 };
 
 #[derive(Debug)]
@@ -16,17 +12,20 @@ pub struct Plane {
 }
 
 impl Plane {
-    pub fn new(normal: Vec3A, pos: Vertex) -> Self {
+    pub fn new<M>(normal: Vec3A, pos: Vertex, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
         Self {
             normal: normal.normalize(),
             pos,
-            material: Box::new(NormalShading::default()),
+            material: Box::new(material),
         }
     }
 }
 
 impl Object for Plane {
-    fn intersection(&self, ray: &Ray) -> Vec<Hit> {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
         let epsilon = 0.0000001;
         let a = self.normal.dot(ray.direction);
         if a > -epsilon && a < epsilon {
@@ -37,19 +36,32 @@ impl Object for Plane {
         let f = w.dot(self.normal);
         let t = f / a;
 
+        let position = ray.position + ray.direction * t;
+        // coordinates across the plane from its reference point
+        let tangent = self.normal.any_orthonormal_vector();
+        let offset = position - self.pos;
+        // outside is the side the normal points to, and the normal is turned to face the ray
+        let entering = a < 0.;
         vec![Hit {
             t,
-            entering: self.normal.dot(ray.direction) < 0.,
+            entering,
             object_hit: self,
             material: &*self.material,
-            position: ray.position + ray.direction * t,
-            normal: self.normal,
+            position,
+            normal: if entering { self.normal } else { -self.normal },
+            tangent,
+            uv: Vec2::new(offset.dot(tangent), offset.dot(self.normal.cross(tangent))),
+            colour: None,
             incident: ray.clone(),
         }]
     }
 
+    fn encloses(&self, ray: &Ray) -> bool {
+        (ray.position - self.pos).dot(self.normal) < 0.
+    }
+
     fn apply_transform(&mut self, t: Affine3A) {
         self.pos = t.transform_point3a(self.pos);
-        self.normal = t.transform_vector3a(self.normal);
+        self.normal = (normal_matrix(t) * self.normal).normalize();
     }
 }
@@ -1,15 +1,21 @@
-use std::fs;
+use std::{f32::consts::PI, fs};
 
-use glam::{Affine3A, Vec3A};
+use glam::{Affine3A, Vec2, Vec3A};
 
 use super::Object;
 use crate::{
-    hit::Hit,
-    material::{Material},
+    aabb::Aabb,
+    colour::Colour,
+    hit::{tangent_around_y, Hit},
+    material::Material,
     ray::Ray,
+    transform::normal_matrix,
     Vertex,
 };
 
+// faces meeting at more than this angle in radians keep a sharp edge when smoothed
+pub const DEFAULT_CREASE_ANGLE: f32 = PI / 3.;
+
 #[derive(Debug)]
 pub struct MeshTriangle {
     pub normal: Vec3A,
@@ -19,13 +25,16 @@ pub struct MeshTriangle {
 #[derive(Debug, Clone)]
 pub struct Corner {
     pub pos: Vertex,
+    pub colour: Option<Colour>,
     pub normal: Vec3A,
+    pub tangent: Vec3A,
 }
 
 #[derive(Debug)]
 pub struct IntermediateTriangle {
     pub normal: Vec3A,
     pub corner_indices: [usize; 3],
+    pub corner_angles: [f32; 3],
 }
 
 #[derive(Debug)]
@@ -37,44 +46,88 @@ pub struct PolyMesh {
 
 impl PolyMesh {
     pub fn new<M>(filename: &str, smoothing: bool, one_ind: bool, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self::with_crease_angle(filename, smoothing, DEFAULT_CREASE_ANGLE, one_ind, material)
+    }
+
+    pub fn with_crease_angle<M>(
+        filename: &str,
+        smoothing: bool,
+        crease_angle: f32,
+        one_ind: bool,
+        material: M,
+    ) -> Self
     where
         M: Material + Send + Sync + 'static,
     {
         // if the .ply file is 1-indexed, adjust accordingly
         let n = if one_ind { 1 } else { 0 };
         let contents = fs::read_to_string(filename).expect("Should read the file");
-        let mut lines = contents.lines();
+        let mut lines = contents.lines().peekable();
         if lines.next() != Some("kcply") {
             panic!("Mesh file doesn't start with kcply");
         }
 
-        // read in number of vertices and faces
-        let line2 = lines.next().expect("Line 2 is right");
-        let vertex_count = line2
-            .strip_prefix("element vertex ")
-            .expect("ln2 starts with element vertex")
-            .parse::<usize>()
-            .expect("suffix is a number");
-        let line3 = lines.next().expect("Line 3 is right");
-        let triangle_count = line3
-            .strip_prefix("element face ")
-            .expect("ln3 starts with element face :)")
-            .parse::<usize>()
-            .expect("suffix is a number");
+        // read in number of vertices and faces, and any vertex properties
+        let mut vertex_count = None;
+        let mut triangle_count = None;
+        let mut element = "";
+        let mut properties = Vec::new();
+        while let Some(l) = lines.next_if(|l| l.starts_with(char::is_alphabetic)) {
+            match l.split_whitespace().collect::<Vec<_>>()[..] {
+                ["element", name, count] => {
+                    element = name;
+                    let count = count.parse::<usize>().expect("suffix is a number");
+                    match name {
+                        "vertex" => vertex_count = Some(count),
+                        "face" => triangle_count = Some(count),
+                        _ => {}
+                    }
+                }
+                ["property", kind, name] if element == "vertex" => properties.push((kind, name)),
+                // comments, face properties and end_header
+                _ => {}
+            }
+        }
+        let vertex_count = vertex_count.expect("Header has element vertex");
+        let triangle_count = triangle_count.expect("Header has element face");
+
+        // files without properties just list x, y and z for each vertex
+        let column = |name: &str| properties.iter().position(|&(_, n)| n == name);
+        let position_columns = if properties.is_empty() {
+            [0, 1, 2]
+        } else {
+            ["x", "y", "z"].map(|a| column(a).expect("Vertex has x, y and z properties"))
+        };
+        let colour_columns = match ["red", "green", "blue"].map(column) {
+            [Some(r), Some(g), Some(b)] => Some([r, g, b]),
+            _ => None,
+        };
 
         let mut vertices = Vec::new();
+        let mut colours = Vec::new();
         for ln in 0..vertex_count {
             let l = lines
                 .next()
-                .expect(format!("Valid line, ln {}", ln).as_str());
+                .unwrap_or_else(|| panic!("Valid line, ln {}", ln));
             let raw_coords = l.split_whitespace();
             let list = raw_coords.collect::<Vec<_>>();
-            let v = Vertex::new(
-                list[0].parse::<f32>().unwrap(),
-                list[1].parse::<f32>().unwrap(),
-                list[2].parse::<f32>().unwrap(),
-            );
-            vertices.push(v);
+            let [x, y, z] = position_columns.map(|i| list[i].parse::<f32>().unwrap());
+            vertices.push(Vertex::new(x, y, z));
+
+            colours.push(colour_columns.map(|columns| {
+                let [r, g, b] = columns.map(|i| {
+                    let value = list[i].parse::<f32>().unwrap();
+                    // integer colour channels run from 0 to 255
+                    match properties[i].0 {
+                        "uchar" | "uint8" => value / 255.,
+                        _ => value,
+                    }
+                });
+                Colour::from_rgb(r, g, b)
+            }));
         }
 
         let mut intermed_triangles = Vec::new();
@@ -82,7 +135,7 @@ impl PolyMesh {
         for ln in 0..triangle_count {
             let l = lines
                 .next()
-                .expect(format!("Valid line, ln {}", ln).as_str());
+                .unwrap_or_else(|| panic!("Valid line, ln {}", ln));
             let raw_verts = l.split_whitespace();
             let list = raw_verts.collect::<Vec<_>>();
             let v = [
@@ -99,26 +152,47 @@ impl PolyMesh {
             let v2 = vertices[v[2]];
             let e1 = v1 - v0;
             let e2 = v2 - v0;
-            let n = e1.cross(e2).normalize();
+            let n = e1.cross(e2).normalize_or_zero();
+
+            // interior angle at each corner, zero for degenerate faces
+            let corner_angles = if n == Vec3A::ZERO {
+                [0.; 3]
+            } else {
+                [
+                    e1.angle_between(e2),
+                    (v2 - v1).angle_between(v0 - v1),
+                    (v0 - v2).angle_between(v1 - v2),
+                ]
+            };
 
             intermed_triangles.push(IntermediateTriangle {
                 normal: n,
                 corner_indices: [v[0], v[1], v[2]],
+                corner_angles,
             });
         }
 
+        let cos_crease = crease_angle.cos();
         let mut triangles = Vec::new();
         for t in intermed_triangles.iter() {
             let corners = t.corner_indices.map(|c| {
-                let neighbours = &*vertex_indices[c];
+                // weight each face by its angle at the vertex, only smoothing across faces
+                // within the crease angle so sharp edges split the vertex normal
                 let mut normal = Vec3A::default();
-                for &n in neighbours {
-                    normal += intermed_triangles[n].normal;
+                for &n in &vertex_indices[c] {
+                    let other = &intermed_triangles[n];
+                    if other.normal.dot(t.normal) >= cos_crease {
+                        let k = other.corner_indices.iter().position(|&i| i == c).unwrap();
+                        normal += other.normal * other.corner_angles[k];
+                    }
                 }
-                normal /= neighbours.len() as f32;
+                let normal = normal.try_normalize().unwrap_or(t.normal);
                 Corner {
                     pos: vertices[c],
+                    colour: colours[c],
                     normal,
+                    // tangents follow the mesh's own y axis so they stay put under transforms
+                    tangent: tangent_around_y(normal),
                 }
             });
             triangles.push(MeshTriangle {
@@ -136,7 +210,7 @@ impl PolyMesh {
 }
 
 impl Object for PolyMesh {
-    fn intersection(&self, ray: &Ray) -> Vec<Hit> {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
         let mut hits = vec![];
         let epsilon = 0.0000001;
         for triangle in &self.triangles {
@@ -154,7 +228,7 @@ impl Object for PolyMesh {
             let f = 1. / a;
             let s = ray.position - c0.pos;
             let u = f * s.dot(h);
-            if u < 0. || u > 1. {
+            if !(0. ..=1.).contains(&u) {
                 continue; // condition from barycentric coords
             }
 
@@ -171,20 +245,38 @@ impl Object for PolyMesh {
                 let mut plane_normal = if self.smoothing {
                     c0.normal * w + c1.normal * u + c2.normal * v
                 } else {
-                    -e1.cross(e2)
+                    triangle.normal
                 };
                 let entering = plane_normal.dot(ray.direction) < 0.;
                 // flip normals for back face hits
                 if !entering {
                     plane_normal = -plane_normal
                 }
+                let plane_normal = plane_normal.normalize();
+                // make the interpolated tangent perpendicular to the normal again
+                let tangent = c0.tangent * w + c1.tangent * u + c2.tangent * v;
+                let tangent = (tangent - tangent.dot(plane_normal) * plane_normal)
+                    .try_normalize()
+                    .unwrap_or_else(|| plane_normal.any_orthonormal_vector());
+                // blend vertex colours with the barycentric coordinates
+                let colour = match (c0.colour, c1.colour, c2.colour) {
+                    (Some(a), Some(b), Some(c)) => Some(Colour::from_rgb(
+                        a.r * w + b.r * u + c.r * v,
+                        a.g * w + b.g * u + c.g * v,
+                        a.b * w + b.b * u + c.b * v,
+                    )),
+                    _ => None,
+                };
                 let h = Hit {
                     t,
                     entering,
                     object_hit: self,
                     material: &*self.material,
                     position: ray.position + ray.direction * t,
-                    normal: plane_normal.normalize(),
+                    normal: plane_normal,
+                    tangent,
+                    uv: Vec2::new(u, v),
+                    colour,
                     incident: ray.clone(),
                 };
                 hits.push(h);
@@ -194,12 +286,19 @@ impl Object for PolyMesh {
     }
 
     fn apply_transform(&mut self, t: Affine3A) {
+        let normal_matrix = normal_matrix(t);
         for triangle in &mut self.triangles {
-            triangle.normal = t.transform_vector3a(triangle.normal);
+            triangle.normal = (normal_matrix * triangle.normal).normalize();
             for corner in &mut triangle.corners {
-                corner.normal = t.transform_vector3a(corner.normal);
+                corner.normal = (normal_matrix * corner.normal).normalize();
+                corner.tangent = t.transform_vector3a(corner.tangent);
                 corner.pos = t.transform_point3a(corner.pos);
             }
         }
     }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let corners = self.triangles.iter().flat_map(|t| &t.corners);
+        Some(Aabb::from_points(corners.map(|c| &c.pos)))
+    }
 }
@@ -0,0 +1,83 @@
+use glam::{Affine3A, Vec2, Vec3A};
+
+use crate::{aabb::Aabb, hit::Hit, material::Material, object::Object, ray::Ray, Vertex};
+
+#[derive(Debug)]
+pub struct Quad {
+    corner: Vertex,
+    edge_u: Vec3A,
+    edge_v: Vec3A,
+    normal: Vec3A,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Quad {
+    // a parallelogram spanning edge_u and edge_v from the corner, facing along edge_u x edge_v
+    pub fn new<M>(corner: Vertex, edge_u: Vec3A, edge_v: Vec3A, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            corner,
+            edge_u,
+            edge_v,
+            normal: edge_u.cross(edge_v).normalize(),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Object for Quad {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let epsilon = 0.0000001;
+        let a = self.normal.dot(ray.direction);
+        if a > -epsilon && a < epsilon {
+            return Vec::new(); // ray parallel to quad
+        }
+
+        let t = (self.corner - ray.position).dot(self.normal) / a;
+        let position = ray.position + ray.direction * t;
+
+        // solve for the coordinates of the hit along each edge
+        let n = self.edge_u.cross(self.edge_v);
+        let w = n / n.dot(n);
+        let p = position - self.corner;
+        let u = w.dot(p.cross(self.edge_v));
+        let v = w.dot(self.edge_u.cross(p));
+        if !(0. ..=1.).contains(&u) || !(0. ..=1.).contains(&v) {
+            return Vec::new();
+        }
+
+        let entering = a < 0.;
+        vec![Hit {
+            t,
+            entering,
+            object_hit: self,
+            material: &*self.material,
+            position,
+            // flip normals for back face hits
+            normal: if entering { self.normal } else { -self.normal },
+            tangent: self.edge_u.normalize(),
+            uv: Vec2::new(u, v),
+            colour: None,
+            incident: ray.clone(),
+        }]
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.corner = t.transform_point3a(self.corner);
+        self.edge_u = t.transform_vector3a(self.edge_u);
+        self.edge_v = t.transform_vector3a(self.edge_v);
+        self.normal = self.edge_u.cross(self.edge_v).normalize();
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let far = self.corner + self.edge_u + self.edge_v;
+        Some(Aabb::from_points(&[
+            self.corner,
+            self.corner + self.edge_u,
+            self.corner + self.edge_v,
+            far,
+        ]))
+    }
+}
@@ -1,20 +1,27 @@
-use glam::{Affine3A, Mat4, Vec3A};
+use glam::{Affine3A, Vec2, Vec3A};
 
+use super::cylinder::axis_frame;
 use crate::{
-    hit::Hit,
-    material::{normalshading::NormalShading, Material},
+    hit::{tangent_around_y, Hit},
+    material::Material,
     object::Object,
     ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
 };
 
 #[derive(Debug)]
 pub struct Quadratic {
+    // coefficients of the surface in its own space, placed in the world by the transform
     coeffs: [f32; 10],
+    transform: Transform,
     material: Box<dyn Material + Send + Sync>,
 }
 
 impl Quadratic {
-    pub fn new(
+    // coefficients of a x^2 + 2b xy + 2c xz + 2d x + e y^2 + 2f yz + 2g y + h z^2 + 2i z + j = 0
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<M>(
         a: f32,
         b: f32,
         c: f32,
@@ -25,19 +32,99 @@ impl Quadratic {
         h: f32,
         i: f32,
         j: f32,
-    ) -> Self {
+        material: M,
+    ) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self::placed([a, b, c, d, e, f, g, h, i, j], Affine3A::IDENTITY, material)
+    }
+
+    pub fn ellipsoid<M>(center: Vertex, radii: Vec3A, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // x^2 + y^2 + z^2 = 1 stretched by the radii
+        Self::placed(
+            [1., 0., 0., 0., 1., 0., 0., 1., 0., -1.],
+            Affine3A::from_scale_rotation_translation(
+                radii.into(),
+                Default::default(),
+                center.into(),
+            ),
+            material,
+        )
+    }
+
+    pub fn paraboloid<M>(vertex: Vertex, axis: Vec3A, radius: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // y = x^2 + z^2, opening along the axis and as wide as the radius at its end
+        Self::placed(
+            [1., 0., 0., 0., 0., 0., -0.5, 1., 0., 0.],
+            axis_frame(vertex, axis, radius),
+            material,
+        )
+    }
+
+    pub fn hyperboloid<M>(center: Vertex, axis: Vec3A, radius: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // x^2 + z^2 - y^2 = 1, one sheet with its narrowest radius at the centre, which has
+        // widened by a factor of root 2 at either end of the axis
+        Self::placed(
+            [1., 0., 0., 0., -1., 0., 0., 1., 0., -1.],
+            axis_frame(center, axis, radius),
+            material,
+        )
+    }
+
+    pub fn hyperboloid_two_sheets<M>(center: Vertex, axis: Vec3A, radius: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // x^2 + z^2 - y^2 = -1, two bowls with their tips at either end of the axis, which are
+        // as wide as the radius at root 2 times the axis length from the centre
+        Self::placed(
+            [1., 0., 0., 0., -1., 0., 0., 1., 0., 1.],
+            axis_frame(center, axis, radius),
+            material,
+        )
+    }
+
+    pub fn cylinder<M>(point: Vertex, axis: Vec3A, radius: f32, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        // x^2 + z^2 = 1, an infinite cylinder through the point along the axis
+        Self::placed(
+            [1., 0., 0., 0., 0., 0., 0., 1., 0., -1.],
+            axis_frame(point, axis, radius),
+            material,
+        )
+    }
+
+    fn placed<M>(coeffs: [f32; 10], transform: Affine3A, material: M) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
         Self {
-            coeffs: [a, b, c, d, e, f, g, h, i, j],
-            material: Box::new(NormalShading::default()),
+            coeffs,
+            transform: Transform::new(transform),
+            material: Box::new(material),
         }
     }
 }
 
 impl Object for Quadratic {
-    fn intersection(&self, ray: &Ray) -> Vec<Hit> {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
         let epsilon = 0.0000001;
-        let [dx, dy, dz] = [ray.direction.x, ray.direction.y, ray.direction.z];
-        let [px, py, pz] = [ray.position.x, ray.position.y, ray.position.z];
+        // intersect in the quadratic's own space
+        let local = self.transform.ray_to_local(ray);
+        let [dx, dy, dz] = [local.direction.x, local.direction.y, local.direction.z];
+        let [px, py, pz] = [local.position.x, local.position.y, local.position.z];
 
         // compute parts
         let aq = self.coeffs[0] * dx.powi(2)
@@ -46,11 +133,6 @@ impl Object for Quadratic {
             + self.coeffs[4] * dy.powi(2)
             + 2. * self.coeffs[5] * dy * dz
             + self.coeffs[7] * dz.powi(2);
-
-        if aq > -epsilon && aq < epsilon {
-            // only one intersection, safely ignore
-            return Vec::new();
-        }
         let bq = 2.
             * (self.coeffs[0] * px * dx
                 + self.coeffs[1] * (px * dy + dx * py)
@@ -71,90 +153,57 @@ impl Object for Quadratic {
             + self.coeffs[7] * pz.powi(2)
             + 2. * self.coeffs[8] * pz
             + self.coeffs[9];
-        let discrim = bq.powi(2) - 4. * aq * cq;
-        if discrim < epsilon {
-            // no intersection (no real roots)
-            return Vec::new();
-        }
-        // two intersections exist
-        let t0 = (-bq - (bq.powi(2) - 4. * aq * cq).powf(0.5)) / (2. * aq);
-        let t1 = (-bq + (bq.powi(2) - 4. * aq * cq).powf(0.5)) / (2. * aq);
 
-        let mut hits = vec![];
-        for t in vec![t0, t1] {
-            let hit_pos = ray.position + t * ray.direction;
-            let mut normal = Vec3A::new(
-                self.coeffs[0] * hit_pos.x
-                    + self.coeffs[1] * hit_pos.y
-                    + self.coeffs[2] * hit_pos.z
-                    + self.coeffs[3],
-                self.coeffs[1] * hit_pos.x
-                    + self.coeffs[4] * hit_pos.y
-                    + self.coeffs[5] * hit_pos.z
-                    + self.coeffs[6],
-                self.coeffs[2] * hit_pos.x
-                    + self.coeffs[5] * hit_pos.y
-                    + self.coeffs[7] * hit_pos.z
-                    + self.coeffs[8],
-            )
-            .normalize();
-            let entering = normal.dot(ray.direction) < 0.;
-            // flip normals for back face hits
-            if !entering {
-                normal = -normal
+        let roots = if aq > -epsilon && aq < epsilon {
+            if bq > -epsilon && bq < epsilon {
+                // the ray runs along the surface or never meets it
+                return Vec::new();
             }
-            let h = Hit {
-                t,
-                entering,
-                object_hit: self,
-                material: &*self.material,
-                position: hit_pos,
-                normal,
-                incident: ray.clone(),
-            };
-            hits.push(h)
-        }
-        hits
+            // the equation is linear, e.g. along the axis of a paraboloid, so one intersection
+            vec![-cq / bq]
+        } else {
+            let discrim = bq.powi(2) - 4. * aq * cq;
+            if discrim < epsilon {
+                // no intersection (no real roots)
+                return Vec::new();
+            }
+            // two intersections exist
+            let t0 = (-bq - discrim.powf(0.5)) / (2. * aq);
+            let t1 = (-bq + discrim.powf(0.5)) / (2. * aq);
+            vec![t0, t1]
+        };
+
+        let hits = roots
+            .into_iter()
+            .map(|t| {
+                let hit_pos = local.position + t * local.direction;
+                let normal = Vec3A::new(
+                    self.coeffs[0] * hit_pos.x
+                        + self.coeffs[1] * hit_pos.y
+                        + self.coeffs[2] * hit_pos.z
+                        + self.coeffs[3],
+                    self.coeffs[1] * hit_pos.x
+                        + self.coeffs[4] * hit_pos.y
+                        + self.coeffs[5] * hit_pos.z
+                        + self.coeffs[6],
+                    self.coeffs[2] * hit_pos.x
+                        + self.coeffs[5] * hit_pos.y
+                        + self.coeffs[7] * hit_pos.z
+                        + self.coeffs[8],
+                );
+                LocalHit {
+                    t,
+                    normal,
+                    tangent: tangent_around_y(normal),
+                    uv: Vec2::ZERO,
+                }
+            })
+            .collect();
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
     }
 
     fn apply_transform(&mut self, t: Affine3A) {
-        // TODO: fix inverted translation
-        let t_matrix = Mat4::from(t);
-        let q_matrix = Mat4::from_cols_array(&[
-            self.coeffs[0],
-            self.coeffs[1],
-            self.coeffs[2],
-            self.coeffs[3],
-            self.coeffs[1],
-            self.coeffs[4],
-            self.coeffs[5],
-            self.coeffs[6],
-            self.coeffs[2],
-            self.coeffs[5],
-            self.coeffs[7],
-            self.coeffs[8],
-            self.coeffs[3],
-            self.coeffs[6],
-            self.coeffs[8],
-            self.coeffs[9],
-        ]);
-        let q_prime = t_matrix.transpose() * q_matrix * t_matrix;
-
-        // extract coefficients from answer
-        [
-            self.coeffs[0],
-            self.coeffs[1],
-            self.coeffs[2],
-            self.coeffs[3],
-        ] = [
-            q_prime.x_axis.x,
-            q_prime.x_axis.y,
-            q_prime.x_axis.z,
-            q_prime.x_axis.w,
-        ];
-        [self.coeffs[4], self.coeffs[5], self.coeffs[6]] =
-            [q_prime.y_axis.y, q_prime.y_axis.z, q_prime.y_axis.w];
-        [self.coeffs[7], self.coeffs[8], self.coeffs[9]] =
-            [q_prime.z_axis.z, q_prime.z_axis.w, q_prime.w_axis.w];
+        self.transform.apply(t);
     }
 }
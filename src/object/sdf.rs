@@ -0,0 +1,200 @@
+use std::fmt;
+
+use glam::{Affine3A, Vec2, Vec3A};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::{tangent_around_y, Hit},
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+pub mod operators;
+pub mod shapes;
+
+const MAX_STEPS: usize = 512;
+const MAX_DISTANCE: f32 = 1000.;
+const EPSILON: f32 = 0.0001;
+
+pub trait Distance: Send + Sync {
+    // signed distance from the point to the surface, negative inside
+    fn distance(&self, p: Vertex) -> f32;
+
+    // radius of a sphere around the origin holding the whole surface, or none if the field is
+    // unbounded, which leaves rays marching out to MAX_DISTANCE
+    fn bounding_radius(&self) -> Option<f32> {
+        None
+    }
+}
+
+impl<F> Distance for F
+where
+    F: Fn(Vertex) -> f32 + Send + Sync,
+{
+    fn distance(&self, p: Vertex) -> f32 {
+        self(p)
+    }
+}
+
+pub struct Sdf {
+    distance: Box<dyn Distance>,
+    step: f32,
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Sdf {
+    pub fn new<D, M>(distance: D, material: M) -> Self
+    where
+        D: Distance + 'static,
+        M: Material + Send + Sync + 'static,
+    {
+        Self::with_step(distance, 1., material)
+    }
+
+    // step is the fraction of the distance to advance by, which should be below 1 for fields
+    // that overestimate the distance such as twists
+    pub fn with_step<D, M>(distance: D, step: f32, material: M) -> Self
+    where
+        D: Distance + 'static,
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            distance: Box::new(distance),
+            step,
+            transform: Transform::default(),
+            material: Box::new(material),
+        }
+    }
+
+    fn normal(&self, p: Vertex) -> Vec3A {
+        // estimate the gradient with the tetrahedron of central differences
+        let k = [
+            Vec3A::new(1., -1., -1.),
+            Vec3A::new(-1., -1., 1.),
+            Vec3A::new(-1., 1., -1.),
+            Vec3A::new(1., 1., 1.),
+        ];
+        k.iter()
+            .fold(Vec3A::ZERO, |sum, &k| {
+                sum + k * self.distance.distance(p + k * EPSILON)
+            })
+            .try_normalize()
+            .unwrap_or(Vec3A::Y)
+    }
+
+    fn extent(&self, ray: &Ray) -> Option<(f32, f32)> {
+        // the stretch of the ray inside the bounding sphere, which the surface can't leave
+        let scale = ray.direction.length();
+        let Some(radius) = self.distance.bounding_radius() else {
+            return Some((0., MAX_DISTANCE / scale));
+        };
+        let radius = radius + EPSILON;
+        let a = ray.direction.length_squared();
+        let b = 2. * ray.position.dot(ray.direction);
+        let c = ray.position.length_squared() - radius * radius;
+        let disc = b * b - 4. * a * c;
+        if disc < 0. {
+            return None;
+        }
+        let far = (-b + disc.sqrt()) / (2. * a);
+        if far < 0. {
+            return None;
+        }
+        Some((((-b - disc.sqrt()) / (2. * a)).max(0.), far))
+    }
+
+    fn march(&self, ray: &Ray, hits: &mut Vec<f32>, first_only: bool) {
+        // sphere trace along the ray, recording the t of each sign change of the field
+        let Some((start, end)) = self.extent(ray) else {
+            return;
+        };
+        let scale = ray.direction.length();
+        let at = |t: f32| self.distance.distance(ray.position + t * ray.direction);
+        let mut t = start;
+        let mut d = at(t);
+        for _ in 0..MAX_STEPS {
+            if t >= end {
+                return;
+            }
+            // never step less than epsilon so the trace can cross the surface
+            let next = (t + (self.step * d.abs()).max(EPSILON) / scale).min(end);
+            let next_d = at(next);
+            if (d < 0.) != (next_d < 0.) {
+                // bisect the crossing to find the surface accurately
+                let (mut a, mut b) = (t, next);
+                for _ in 0..16 {
+                    let mid = (a + b) / 2.;
+                    if (at(mid) < 0.) == (d < 0.) {
+                        a = mid;
+                    } else {
+                        b = mid;
+                    }
+                }
+                hits.push((a + b) / 2.);
+                if first_only {
+                    return;
+                }
+            }
+            t = next;
+            d = next_d;
+        }
+    }
+}
+
+impl fmt::Debug for Sdf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sdf")
+            .field("step", &self.step)
+            .field("transform", &self.transform)
+            .field("material", &self.material)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Object for Sdf {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let local = self.transform.ray_to_local(ray);
+        let mut ts = Vec::new();
+
+        // rays starting inside also need the entry behind them to keep csg intervals paired
+        if self.distance.distance(local.position) < 0. {
+            let mut behind = Vec::new();
+            self.march(
+                &local.spawn(local.position, -local.direction),
+                &mut behind,
+                true,
+            );
+            ts.extend(behind.iter().map(|t| -t));
+        }
+        self.march(&local, &mut ts, false);
+
+        let hits = ts
+            .into_iter()
+            .map(|t| {
+                let p = local.position + t * local.direction;
+                let normal = self.normal(p);
+                LocalHit {
+                    t,
+                    normal,
+                    tangent: tangent_around_y(normal),
+                    uv: Vec2::ZERO,
+                }
+            })
+            .collect();
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let r = self.distance.bounding_radius()?;
+        Some(Aabb::new(Vertex::splat(-r), Vertex::splat(r)).transformed(self.transform.to_world()))
+    }
+}
@@ -0,0 +1,181 @@
+use glam::Vec3A;
+
+use super::Distance;
+use crate::Vertex;
+
+// operators that build new distance fields from existing ones
+
+#[derive(Debug)]
+pub struct Union<A, B>(pub A, pub B);
+
+impl<A: Distance, B: Distance> Distance for Union<A, B> {
+    fn distance(&self, p: Vertex) -> f32 {
+        self.0.distance(p).min(self.1.distance(p))
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        Some(self.0.bounding_radius()?.max(self.1.bounding_radius()?))
+    }
+}
+
+#[derive(Debug)]
+pub struct Intersection<A, B>(pub A, pub B);
+
+impl<A: Distance, B: Distance> Distance for Intersection<A, B> {
+    fn distance(&self, p: Vertex) -> f32 {
+        self.0.distance(p).max(self.1.distance(p))
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        // inside both, so within whichever is bounded
+        match (self.0.bounding_radius(), self.1.bounding_radius()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+// the first field with the second cut away
+#[derive(Debug)]
+pub struct Subtraction<A, B>(pub A, pub B);
+
+impl<A: Distance, B: Distance> Distance for Subtraction<A, B> {
+    fn distance(&self, p: Vertex) -> f32 {
+        self.0.distance(p).max(-self.1.distance(p))
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        self.0.bounding_radius()
+    }
+}
+
+#[derive(Debug)]
+pub struct SmoothUnion<A, B> {
+    pub first: A,
+    pub second: B,
+    pub smoothness: f32,
+}
+
+impl<A: Distance, B: Distance> Distance for SmoothUnion<A, B> {
+    fn distance(&self, p: Vertex) -> f32 {
+        // polynomial smooth minimum, blending over a band of width smoothness
+        let (a, b, k) = (
+            self.first.distance(p),
+            self.second.distance(p),
+            self.smoothness,
+        );
+        let h = (0.5 + 0.5 * (b - a) / k).clamp(0., 1.);
+        b + (a - b) * h - k * h * (1. - h)
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        // the blend only swells the union by a quarter of the smoothness
+        let a = self.first.bounding_radius()?;
+        let b = self.second.bounding_radius()?;
+        Some(a.max(b) + self.smoothness / 4.)
+    }
+}
+
+#[derive(Debug)]
+pub struct SmoothSubtraction<A, B> {
+    pub first: A,
+    pub second: B,
+    pub smoothness: f32,
+}
+
+impl<A: Distance, B: Distance> Distance for SmoothSubtraction<A, B> {
+    fn distance(&self, p: Vertex) -> f32 {
+        let (a, b, k) = (
+            self.first.distance(p),
+            self.second.distance(p),
+            self.smoothness,
+        );
+        let h = (0.5 - 0.5 * (a + b) / k).clamp(0., 1.);
+        a + (-b - a) * h + k * h * (1. - h)
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        // the smooth maximum never goes below the first field
+        self.first.bounding_radius()
+    }
+}
+
+#[derive(Debug)]
+pub struct Twist<A> {
+    pub inner: A,
+    // radians turned around the y axis per unit of height
+    pub rate: f32,
+}
+
+impl<A: Distance> Distance for Twist<A> {
+    fn distance(&self, p: Vertex) -> f32 {
+        let (s, c) = (self.rate * p.y).sin_cos();
+        self.inner
+            .distance(Vec3A::new(c * p.x - s * p.z, p.y, s * p.x + c * p.z))
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        // turning about the y axis keeps points the same distance from the origin
+        self.inner.bounding_radius()
+    }
+}
+
+#[derive(Debug)]
+pub struct Repeat<A> {
+    pub inner: A,
+    // spacing of the copies along each axis, with zero meaning no repetition
+    pub period: Vec3A,
+}
+
+impl<A: Distance> Distance for Repeat<A> {
+    fn distance(&self, p: Vertex) -> f32 {
+        let mut q = p;
+        for axis in 0..3 {
+            let period = self.period[axis];
+            if period > 0. {
+                q[axis] -= period * (p[axis] / period).round();
+            }
+        }
+        self.inner.distance(q)
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        if self.period.cmpgt(Vec3A::ZERO).any() {
+            return None;
+        }
+        self.inner.bounding_radius()
+    }
+}
+
+#[derive(Debug)]
+pub struct Round<A> {
+    pub inner: A,
+    pub radius: f32,
+}
+
+impl<A: Distance> Distance for Round<A> {
+    fn distance(&self, p: Vertex) -> f32 {
+        self.inner.distance(p) - self.radius
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        Some(self.inner.bounding_radius()? + self.radius)
+    }
+}
+
+// gives a field, such as a closure, a known radius so rays can skip it when they pass outside
+#[derive(Debug)]
+pub struct Bounded<A> {
+    pub inner: A,
+    pub radius: f32,
+}
+
+impl<A: Distance> Distance for Bounded<A> {
+    fn distance(&self, p: Vertex) -> f32 {
+        self.inner.distance(p)
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        Some(self.radius)
+    }
+}
@@ -0,0 +1,88 @@
+use glam::{Vec2, Vec3A};
+
+use super::Distance;
+use crate::Vertex;
+
+// primitive distance fields, centred on the origin
+
+#[derive(Debug)]
+pub struct Ball {
+    pub radius: f32,
+}
+
+impl Distance for Ball {
+    fn distance(&self, p: Vertex) -> f32 {
+        p.length() - self.radius
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        Some(self.radius)
+    }
+}
+
+#[derive(Debug)]
+pub struct Cuboid {
+    pub half_size: Vec3A,
+}
+
+impl Distance for Cuboid {
+    fn distance(&self, p: Vertex) -> f32 {
+        let q = p.abs() - self.half_size;
+        q.max(Vec3A::ZERO).length() + q.max_element().min(0.)
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        Some(self.half_size.length())
+    }
+}
+
+#[derive(Debug)]
+pub struct Ring {
+    pub major_radius: f32,
+    pub minor_radius: f32,
+}
+
+impl Distance for Ring {
+    fn distance(&self, p: Vertex) -> f32 {
+        // a torus around the y axis
+        let q = Vec2::new(Vec2::new(p.x, p.z).length() - self.major_radius, p.y);
+        q.length() - self.minor_radius
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        Some(self.major_radius + self.minor_radius)
+    }
+}
+
+#[derive(Debug)]
+pub struct Capsule {
+    pub start: Vertex,
+    pub end: Vertex,
+    pub radius: f32,
+}
+
+impl Distance for Capsule {
+    fn distance(&self, p: Vertex) -> f32 {
+        let pa = p - self.start;
+        let ba = self.end - self.start;
+        let h = (pa.dot(ba) / ba.dot(ba)).clamp(0., 1.);
+        (pa - ba * h).length() - self.radius
+    }
+
+    fn bounding_radius(&self) -> Option<f32> {
+        Some(self.start.length().max(self.end.length()) + self.radius)
+    }
+}
+
+#[derive(Debug)]
+pub struct HalfSpace {
+    pub normal: Vec3A,
+    pub offset: f32,
+}
+
+impl Distance for HalfSpace {
+    fn distance(&self, p: Vertex) -> f32 {
+        // everything below the plane at offset along the unit normal
+        p.dot(self.normal) - self.offset
+    }
+}
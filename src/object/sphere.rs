@@ -1,12 +1,20 @@
-use glam::{Affine3A, Vec3A};
+use std::f32::consts::PI;
+
+use glam::{Affine3A, Vec2, Vec3, Vec3A};
 
 use super::Object;
-use crate::{hit::Hit, material::Material, ray::Ray, Vertex};
+use crate::{
+    aabb::Aabb,
+    hit::{tangent_around_y, Hit},
+    material::Material,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
 
 #[derive(Debug)]
 pub struct Sphere {
-    center: Vertex,
-    radius: f32,
+    transform: Transform,
     material: Box<dyn Material + Send + Sync>,
 }
 
@@ -15,26 +23,26 @@ impl Sphere {
     where
         M: Material + Send + Sync + 'static,
     {
+        // stored as a unit sphere placed by its transform, so any scale makes an ellipsoid
         Self {
-            center,
-            radius,
+            transform: Transform::new(Affine3A::from_scale_rotation_translation(
+                Vec3::splat(radius),
+                Default::default(),
+                center.into(),
+            )),
             material: Box::new(material),
         }
     }
 }
 
 impl Object for Sphere {
-    fn intersection(&self, ray: &Ray) -> Vec<Hit> {
-        // offset ray by sphere position
-        let ro = Vec3A::new(
-            ray.position.x - self.center.x,
-            ray.position.y - self.center.y,
-            ray.position.z - self.center.z,
-        );
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        let local = self.transform.ray_to_local(ray);
+        let ro = local.position;
 
-        let a = ray.direction.dot(ray.direction);
-        let b = 2. * ray.direction.dot(ro);
-        let c = ro.dot(ro) - self.radius.powi(2);
+        let a = local.direction.dot(local.direction);
+        let b = 2. * local.direction.dot(ro);
+        let c = ro.dot(ro) - 1.;
 
         let disc = b.powi(2) - 4. * a * c;
 
@@ -43,45 +51,40 @@ impl Object for Sphere {
         } else {
             let ds = disc.sqrt();
 
-            let t0 = (-b - ds) / 2.;
-            let t1 = (-b + ds) / 2.;
-
-            let position0 = ray.position + t0 * ray.direction;
-            let mut hit0 = Hit {
-                t: t0,
-                entering: true,
-                object_hit: self,
-                material: &*self.material,
-                position: position0,
-                normal: (position0 - self.center).normalize(),
-                incident: ray.clone(),
-            };
-
-            if hit0.normal.dot(ray.direction) > 0. {
-                hit0.normal *= -1.;
-            }
-
-            let position1 = ray.position + t1 * ray.direction;
-            let mut hit1 = Hit {
-                t: t1,
-                entering: false,
-                object_hit: self,
-                material: &*self.material,
-                position: position1,
-                normal: (position1 - self.center).normalize(),
-                incident: ray.clone(),
-            };
-
-            if hit1.normal.dot(ray.direction) > 0. {
-                hit1.normal *= -1.;
-            }
+            let t0 = (-b - ds) / (2. * a);
+            let t1 = (-b + ds) / (2. * a);
 
-            vec![hit0, hit1]
+            let hits = [t0, t1]
+                .map(|t| {
+                    // on a unit sphere the position is also the outward normal
+                    let p = ro + t * local.direction;
+                    LocalHit {
+                        t,
+                        normal: p,
+                        tangent: tangent_around_y(p),
+                        uv: sphere_uv(p),
+                    }
+                })
+                .to_vec();
+            self.transform
+                .hits_to_world(ray, hits, self, &*self.material)
         }
     }
 
     fn apply_transform(&mut self, t: Affine3A) {
-        let new = t.transform_point3a(self.center);
-        self.center = new;
+        self.transform.apply(t);
     }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Aabb::new(-Vec3A::ONE, Vec3A::ONE).transformed(self.transform.to_world()))
+    }
+}
+
+fn sphere_uv(p: Vec3A) -> Vec2 {
+    // longitude around the y axis and latitude from the south pole
+    let p = p.normalize();
+    Vec2::new(
+        0.5 + p.z.atan2(p.x) / (2. * PI),
+        0.5 + p.y.clamp(-1., 1.).asin() / PI,
+    )
 }
@@ -0,0 +1,107 @@
+use std::f32::consts::PI;
+
+use glam::{Affine3A, Quat, Vec2, Vec3, Vec3A};
+
+use super::Object;
+use crate::{
+    aabb::Aabb,
+    hit::{tangent_around_y, Hit},
+    material::Material,
+    polynomial::solve_quartic,
+    ray::Ray,
+    transform::{LocalHit, Transform},
+    Vertex,
+};
+
+#[derive(Debug)]
+pub struct Torus {
+    major_radius: f32,
+    minor_radius: f32,
+    transform: Transform,
+    material: Box<dyn Material + Send + Sync>,
+}
+
+impl Torus {
+    // a ring around the axis through the centre, with the tube's radius given by minor_radius
+    pub fn new<M>(
+        center: Vertex,
+        axis: Vec3A,
+        major_radius: f32,
+        minor_radius: f32,
+        material: M,
+    ) -> Self
+    where
+        M: Material + Send + Sync + 'static,
+    {
+        Self {
+            major_radius,
+            minor_radius,
+            transform: Transform::new(Affine3A::from_rotation_translation(
+                Quat::from_rotation_arc(Vec3::Y, axis.normalize().into()),
+                center.into(),
+            )),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Object for Torus {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        // local torus lies in the xz plane around the y axis
+        let local = self.transform.ray_to_local(ray);
+        let (big_r, small_r) = (self.major_radius as f64, self.minor_radius as f64);
+        let d = local.direction.as_dvec3();
+        let m = d.dot(d);
+
+        // start from the point on the ray closest to the centre to keep the coefficients small
+        let offset = -local.position.as_dvec3().dot(d) / m;
+        let p = local.position.as_dvec3() + offset * d;
+
+        // expand (|x|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + z^2) along the ray
+        let n = p.dot(d);
+        let k = p.dot(p) + big_r * big_r - small_r * small_r;
+        let four_r2 = 4. * big_r * big_r;
+        let roots = solve_quartic(
+            m * m,
+            4. * m * n,
+            4. * n * n + 2. * m * k - four_r2 * (d.x * d.x + d.z * d.z),
+            4. * n * k - 2. * four_r2 * (p.x * d.x + p.z * d.z),
+            k * k - four_r2 * (p.x * p.x + p.z * p.z),
+        );
+
+        let hits = roots
+            .into_iter()
+            .map(|t| {
+                let t = (t + offset) as f32;
+                let q = local.position + t * local.direction;
+                // the normal points away from the nearest point on the ring through the tube
+                let ring = Vec3A::new(q.x, 0., q.z).normalize_or_zero() * self.major_radius;
+                let normal = (q - ring).normalize();
+                let around = 0.5 + q.z.atan2(q.x) / (2. * PI);
+                let tube = 0.5
+                    + normal
+                        .y
+                        .atan2(Vec2::new(q.x, q.z).length() - self.major_radius)
+                        / (2. * PI);
+                LocalHit {
+                    t,
+                    normal,
+                    tangent: tangent_around_y(ring),
+                    uv: Vec2::new(around, tube),
+                }
+            })
+            .collect();
+        self.transform
+            .hits_to_world(ray, hits, self, &*self.material)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.transform.apply(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3A::new(outer, self.minor_radius, outer);
+        Some(Aabb::new(-extent, extent).transformed(self.transform.to_world()))
+    }
+}
@@ -1,10 +1,7 @@
-use glam::{Affine3A, Vec3A};
+use glam::{Affine3A, Vec2, Vec3A};
 
 use crate::{
-    hit::Hit,
-    material::{Material},
-    object::Object,
-    ray::Ray,
+    aabb::Aabb, hit::Hit, material::Material, object::Object, ray::Ray, transform::normal_matrix,
     Vertex,
 };
 
@@ -32,9 +29,9 @@ impl Triangle {
 }
 
 impl Object for Triangle {
-    fn intersection(&self, ray: &Ray) -> Vec<Hit> {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
         let epsilon = 0.0001;
-        let [c0, c1, c2] = self.corners.clone();
+        let [c0, c1, c2] = self.corners;
 
         // implementing the MT algorithm which exploits Cramer's rule
         let e1 = c1 - c0;
@@ -48,7 +45,7 @@ impl Object for Triangle {
         let f = 1. / a;
         let s = ray.position - c0;
         let u = f * s.dot(h);
-        if u < 0. || u > 1. {
+        if !(0. ..=1.).contains(&u) {
             return Vec::new(); // condition from barycentric coords
         }
 
@@ -61,19 +58,24 @@ impl Object for Triangle {
         let t = f * e2.dot(q);
         if t > epsilon {
             // successful ray intersection
-            let plane_normal = e1.cross(e2);
-            let normal = if plane_normal.dot(ray.direction) < 0. {
+            // outside is the side the corners wind anticlockwise around, as for polymeshes
+            let plane_normal = self.normal;
+            let entering = plane_normal.dot(ray.direction) < 0.;
+            let normal = if entering {
                 plane_normal
             } else {
                 -plane_normal
             };
             vec![Hit {
                 t,
-                entering: true,
+                entering,
                 object_hit: self,
                 material: &*self.material,
                 position: ray.position + ray.direction * t,
                 normal: normal.normalize(),
+                tangent: e1.normalize(),
+                uv: Vec2::new(u, v),
+                colour: None,
                 incident: ray.clone(),
             }]
         } else {
@@ -82,9 +84,13 @@ impl Object for Triangle {
     }
 
     fn apply_transform(&mut self, t: Affine3A) {
-        self.normal = t.transform_vector3a(self.normal);
+        self.normal = (normal_matrix(t) * self.normal).normalize();
         for corner in &mut self.corners {
             *corner = t.transform_point3a(*corner);
         }
     }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Aabb::from_points(&self.corners))
+    }
 }
@@ -0,0 +1,96 @@
+use std::cmp::Ordering;
+
+use glam::{Affine3A, Vec2};
+
+use crate::{aabb::Aabb, hit::Hit, medium::Medium, object::Object, ray::Ray};
+
+#[derive(Debug)]
+pub struct Volume {
+    boundary: Option<Box<dyn Object + Send + Sync>>,
+    medium: Box<dyn Medium>,
+}
+
+impl Volume {
+    pub fn new<O, M>(boundary: O, medium: M) -> Self
+    where
+        O: Object + Send + Sync + 'static,
+        M: Medium + 'static,
+    {
+        // fill the interior of a closed object with a medium
+        Self {
+            boundary: Some(Box::new(boundary)),
+            medium: Box::new(medium),
+        }
+    }
+
+    pub fn global<M>(medium: M) -> Self
+    where
+        M: Medium + 'static,
+    {
+        // a medium filling the whole scene
+        Self {
+            boundary: None,
+            medium: Box::new(medium),
+        }
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(f32, f32)> {
+        // find the spans of the ray that lie inside the boundary
+        let Some(boundary) = &self.boundary else {
+            return vec![(0., f32::INFINITY)];
+        };
+        let mut hits = boundary.intersection(ray);
+        hits.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal));
+
+        let mut intervals = Vec::new();
+        // the ray starts inside if the first boundary hit is leaving the object
+        let mut start = match hits.first() {
+            Some(h) if !h.entering => Some(0.),
+            _ => None,
+        };
+        for hit in hits {
+            if hit.entering {
+                start = Some(hit.t);
+            } else if let Some(s) = start.take() {
+                if hit.t > 0. {
+                    intervals.push((f32::max(s, 0.), hit.t));
+                }
+            }
+        }
+        intervals
+    }
+}
+
+impl Object for Volume {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        for (t_min, t_max) in self.intervals(ray) {
+            if let Some(t) = self.medium.sample_distance(ray, t_min, t_max) {
+                // scattering events are not surfaces, so nothing is entered
+                return vec![Hit {
+                    t,
+                    entering: false,
+                    object_hit: self,
+                    material: self.medium.phase(),
+                    position: ray.position + t * ray.direction,
+                    normal: -ray.direction.normalize(),
+                    tangent: ray.direction.any_orthonormal_vector(),
+                    uv: Vec2::ZERO,
+                    colour: None,
+                    incident: ray.clone(),
+                }];
+            }
+        }
+        Vec::new()
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        if let Some(boundary) = &mut self.boundary {
+            boundary.apply_transform(t);
+        }
+        self.medium.apply_transform(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.boundary.as_ref()?.bounds()
+    }
+}
@@ -1,9 +1,16 @@
+use std::f32::consts::PI;
+
 use acap::{kd::KdTree, Coordinates, Euclidean, EuclideanDistance, NearestNeighbors, Proximity};
+use glam::Vec3A;
 
 use crate::{colour::Colour, hit::Hit, ray::Ray, scene::Scene, Vertex};
 
+// photons fired from each light when building the main map
+const PHOTONS_PER_LIGHT: usize = 50000;
+
 pub struct PhotonMap<'a> {
     tree: KdTree<PhotonHit<'a>>,
+    volume_tree: KdTree<PhotonHit<'a>>,
 }
 
 pub struct PhotonHit<'a> {
@@ -28,49 +35,56 @@ pub enum Type {
 pub enum Interaction {
     Reflected { ray: Ray, attenuation: Colour },
     Transmitted { ray: Ray, attenuation: Colour },
+    Scattered { ray: Ray, attenuation: Colour },
     Absorbed,
 }
 
 impl<'a> PhotonMap<'a> {
     pub fn build(scene: &'a Scene) -> Self {
         let mut tree = KdTree::new();
-        let photon_number = 50000;
-
+        let mut volume_tree = KdTree::new();
         for light in &scene.light_list {
-            for _ in 0..photon_number {
+            for _ in 0..PHOTONS_PER_LIGHT {
                 let mut p = light.generate_photon();
                 let mut depth = 5;
                 'l: while let Some(ph) = Self::photon_trace(scene, p) {
                     let c = ph.photon.colour;
-                    if ph.photon.type_ == Type::Direct {
-                        // fire shadow photon
-                        if let Some(ph) = Self::shadow_photon_trace(
-                            scene,
-                            Photon {
-                                ray: Ray::new(
-                                    ph.hit.position + 0.0001 * ph.hit.incident.direction,
-                                    ph.hit.incident.direction,
-                                ),
-                                colour: Colour::from_rgb(0., 0., 0.),
-                                type_: Type::Shadow,
-                            },
-                        ) {
-                            tree.push(ph);
+                    let hit = ph.hit.clone();
+                    let interaction = hit.material.interact(&hit);
+
+                    if let Interaction::Scattered { .. } = interaction {
+                        // photons scattered inside a medium are stored as volume photons
+                        volume_tree.push(ph);
+                    } else {
+                        if ph.photon.type_ == Type::Direct {
+                            // fire shadow photon
+                            if let Some(ph) = Self::shadow_photon_trace(
+                                scene,
+                                Photon {
+                                    ray: ph.hit.incident.spawn(
+                                        ph.hit.position + 0.0001 * ph.hit.incident.direction,
+                                        ph.hit.incident.direction,
+                                    ),
+                                    colour: Colour::from_rgb(0., 0., 0.),
+                                    type_: Type::Shadow,
+                                },
+                            ) {
+                                tree.push(ph);
+                            }
                         }
+                        tree.push(ph);
                     }
 
-                    let hit = ph.hit.clone();
-                    tree.push(ph);
-
                     if depth < 1 {
                         break 'l;
                     } else {
                         depth -= 1;
                     }
 
-                    match hit.material.interact(&hit) {
+                    match interaction {
                         Interaction::Reflected { ray, attenuation }
-                        | Interaction::Transmitted { ray, attenuation } => {
+                        | Interaction::Transmitted { ray, attenuation }
+                        | Interaction::Scattered { ray, attenuation } => {
                             p = Photon {
                                 ray,
                                 colour: attenuation * c,
@@ -86,7 +100,8 @@ impl<'a> PhotonMap<'a> {
         }
 
         tree.balance();
-        PhotonMap { tree }
+        volume_tree.balance();
+        PhotonMap { tree, volume_tree }
     }
 
     pub fn build_caustics(scene: &'a Scene) -> Self {
@@ -109,7 +124,9 @@ impl<'a> PhotonMap<'a> {
                             Interaction::Transmitted { .. } => {
                                 tree.push(ph);
                             }
-                            Interaction::Reflected { .. } | Interaction::Absorbed => {
+                            Interaction::Reflected { .. }
+                            | Interaction::Scattered { .. }
+                            | Interaction::Absorbed => {
                                 break 'l;
                             }
                         }
@@ -131,7 +148,9 @@ impl<'a> PhotonMap<'a> {
                                 type_: Type::Indirect,
                             }
                         }
-                        Interaction::Reflected { .. } | Interaction::Absorbed => {
+                        Interaction::Reflected { .. }
+                        | Interaction::Scattered { .. }
+                        | Interaction::Absorbed => {
                             break 'l;
                         }
                     }
@@ -140,34 +159,32 @@ impl<'a> PhotonMap<'a> {
         }
 
         tree.balance();
-        PhotonMap { tree }
+        PhotonMap {
+            tree,
+            volume_tree: KdTree::new(),
+        }
     }
 
     pub fn photon_trace(scene: &'a Scene, photon: Photon) -> Option<PhotonHit<'a>> {
         // indirect photons
-        if let Some(h) = scene.trace(&photon.ray) {
-            Some(PhotonHit {
-                photon: photon,
-                hit: h,
-            })
-        } else {
-            None
-        }
+        scene
+            .trace(&photon.ray)
+            .map(|h| PhotonHit { photon, hit: h })
     }
 
     pub fn shadow_photon_trace(scene: &'a Scene, photon: Photon) -> Option<PhotonHit<'a>> {
         // shadow photons
         if let Some(h) = scene.trace(&photon.ray) {
-            if h.entering {
-                Some(PhotonHit {
-                    photon: photon,
-                    hit: h,
-                })
+            if h.material.is_medium() {
+                // shadow photons only mark surfaces, so stop at the first medium event
+                None
+            } else if h.entering {
+                Some(PhotonHit { photon, hit: h })
             } else {
                 PhotonMap::shadow_photon_trace(
                     scene,
                     Photon {
-                        ray: Ray::new(
+                        ray: h.incident.spawn(
                             h.position + 0.0001 * h.incident.direction,
                             h.incident.direction,
                         ),
@@ -195,6 +212,26 @@ impl<'a> PhotonMap<'a> {
         (colour, n)
     }
 
+    pub fn volume_radiance(&self, pos: Vertex, phase: impl Fn(Vec3A) -> f32) -> Colour {
+        // light scattered towards the viewer by the volume photons around a point in a medium,
+        // given the phase for each photon's direction of travel
+        // direct light is left out as shadow rays towards the lights already find it
+        let neighbours = 200;
+        let radius = 0.3;
+        let nearest = self
+            .volume_tree
+            .k_nearest_within(&[pos.x, pos.y, pos.z], neighbours, radius);
+        let volume = 4. / 3. * PI * radius.powi(3);
+        nearest
+            .into_iter()
+            .filter(|n| n.item.photon.type_ != Type::Direct)
+            .fold(Colour::default(), |colour, n| {
+                let photon = &n.item.photon;
+                colour + photon.colour * phase(photon.ray.direction.normalize())
+            })
+            / (PHOTONS_PER_LIGHT as f32 * volume)
+    }
+
     pub fn visualise_caustics(&self, pos: Vertex) -> (Colour, usize) {
         let neighbours = 10000;
         let radius = 0.4;
@@ -205,7 +242,9 @@ impl<'a> PhotonMap<'a> {
         let mut n = 0;
         for hit in nearest {
             match hit.item.hit.material.interact(&hit.item.hit) {
-                Interaction::Reflected { .. } | Interaction::Transmitted { .. } => {}
+                Interaction::Reflected { .. }
+                | Interaction::Transmitted { .. }
+                | Interaction::Scattered { .. } => {}
                 Interaction::Absorbed => {
                     n += 1;
                     colour += hit.item.photon.colour;
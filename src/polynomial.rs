@@ -0,0 +1,160 @@
+// real roots of low order polynomials, in double precision and sorted in ascending order
+
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0. {
+        return if b == 0. { vec![] } else { vec![-c / b] };
+    }
+    let disc = b * b - 4. * a * c;
+    if disc < 0. {
+        return vec![];
+    }
+    // avoid cancellation by computing the larger root first
+    let q = -0.5 * (b + b.signum() * disc.sqrt());
+    let mut roots = if q == 0. {
+        vec![0., 0.]
+    } else {
+        vec![q / a, c / q]
+    };
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a == 0. {
+        return solve_quadratic(b, c, d);
+    }
+    let (b, c, d) = (b / a, c / a, d / a);
+
+    // depress with x = y - b / 3 to give y^3 + p y + q
+    let shift = b / 3.;
+    let p = c - b * b / 3.;
+    let q = 2. * b * b * b / 27. - b * c / 3. + d;
+    let disc = (q / 2.).powi(2) + (p / 3.).powi(3);
+
+    let mut roots = if disc > 0. {
+        // one real root from cardano's formula
+        let s = disc.sqrt();
+        vec![(-q / 2. + s).cbrt() + (-q / 2. - s).cbrt()]
+    } else if p == 0. {
+        vec![0.]
+    } else {
+        // three real roots from the trigonometric form
+        let m = 2. * (-p / 3.).sqrt();
+        let theta = (3. * q / (p * m)).clamp(-1., 1.).acos() / 3.;
+        (0..3)
+            .map(|k| m * (theta - 2. * std::f64::consts::PI * k as f64 / 3.).cos())
+            .collect()
+    };
+    for r in &mut roots {
+        *r -= shift;
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a == 0. {
+        return solve_cubic(b, c, d, e);
+    }
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // depress with x = y - b / 4 to give y^4 + p y^2 + q y + r
+    let shift = b / 4.;
+    let p = c - 3. * b * b / 8.;
+    let q = d - b * c / 2. + b * b * b / 8.;
+    let r = e - b * d / 4. + b * b * c / 16. - 3. * b.powi(4) / 256.;
+
+    let mut roots = if q.abs() < 1e-12 {
+        // biquadratic, so solve for y^2
+        solve_quadratic(1., p, r)
+            .into_iter()
+            .filter(|&z| z >= 0.)
+            .flat_map(|z| [-z.sqrt(), z.sqrt()])
+            .collect()
+    } else {
+        // ferrari's method: the largest root of the resolvent cubic is always positive here
+        let m = solve_cubic(8., 8. * p, 2. * p * p - 8. * r, -q * q)
+            .into_iter()
+            .fold(0., f64::max);
+        let s = (2. * m).sqrt();
+        let mut roots = solve_quadratic(1., -s, p / 2. + m + q / (2. * s));
+        roots.extend(solve_quadratic(1., s, p / 2. + m - q / (2. * s)));
+        roots
+    };
+
+    // polish with newton's method on the original polynomial to tidy up rounding errors
+    for x in &mut roots {
+        *x -= shift;
+        for _ in 0..4 {
+            let f = (((*x + b) * *x + c) * *x + d) * *x + e;
+            let df = ((4. * *x + 3. * b) * *x + 2. * c) * *x + d;
+            if df == 0. {
+                break;
+            }
+            *x -= f / df;
+        }
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+pub fn solve_in_range(coefficients: &[f64], lo: f64, hi: f64) -> Vec<f64> {
+    // roots of a polynomial of any degree between lo and hi, with the coefficients given
+    // from the highest power down. the roots of the derivative split the range into pieces
+    // where the polynomial is monotonic, so each piece holds at most one root to bisect for
+    let start = coefficients
+        .iter()
+        .position(|&c| c != 0.)
+        .unwrap_or(coefficients.len());
+    let coefficients = &coefficients[start..];
+    let degree = match coefficients.len() {
+        0 | 1 => return vec![],
+        n => n - 1,
+    };
+    if degree == 1 {
+        let root = -coefficients[1] / coefficients[0];
+        return if (lo..=hi).contains(&root) {
+            vec![root]
+        } else {
+            vec![]
+        };
+    }
+
+    let derivative = coefficients[..degree]
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c * (degree - i) as f64)
+        .collect::<Vec<_>>();
+    let mut bounds = vec![lo];
+    bounds.extend(solve_in_range(&derivative, lo, hi));
+    bounds.push(hi);
+
+    let evaluate = |x: f64| coefficients.iter().fold(0., |sum, c| sum * x + c);
+    let mut roots: Vec<f64> = Vec::new();
+    for pair in bounds.windows(2) {
+        let (mut a, mut b) = (pair[0], pair[1]);
+        let (fa, fb) = (evaluate(a), evaluate(b));
+        let root = if fa == 0. {
+            a
+        } else if fb == 0. {
+            b
+        } else if (fa < 0.) == (fb < 0.) {
+            continue;
+        } else {
+            for _ in 0..64 {
+                let mid = (a + b) / 2.;
+                if (evaluate(mid) < 0.) == (fa < 0.) {
+                    a = mid;
+                } else {
+                    b = mid;
+                }
+            }
+            (a + b) / 2.
+        };
+        // roots landing on a shared bound would otherwise be found twice
+        if roots.last().is_none_or(|&last| root - last > 1e-9) {
+            roots.push(root);
+        }
+    }
+    roots
+}
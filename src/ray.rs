@@ -2,19 +2,12 @@ use glam::Vec3A;
 
 use crate::Vertex;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Ray {
     pub position: Vertex,
     pub direction: Vec3A,
-}
-
-impl Default for Ray {
-    fn default() -> Self {
-        Self {
-            position: Vertex::default(),
-            direction: Vec3A::default(),
-        }
-    }
+    // moment within the camera's shutter, which moving objects are placed at
+    pub time: f32,
 }
 
 impl Ray {
@@ -22,8 +15,18 @@ impl Ray {
         Self {
             position,
             direction,
+            time: 0.,
         }
     }
+
+    pub fn with_time(self, time: f32) -> Self {
+        Self { time, ..self }
+    }
+
+    pub fn spawn(&self, position: Vertex, direction: Vec3A) -> Self {
+        // a new ray leaving from the same moment, such as a reflection or shadow ray
+        Self::new(position, direction).with_time(self.time)
+    }
 }
 
 pub trait Reflectable {
@@ -33,7 +36,7 @@ pub trait Reflectable {
 impl Reflectable for Vec3A {
     fn reflect(&self, normal: Vec3A) -> Self {
         // function expects self(incident ray) to point towards surface
-        let d = normal.dot(self.clone()) * 2.;
+        let d = normal.dot(*self) * 2.;
         Vec3A::new(
             self.x - d * normal.x,
             self.y - d * normal.y,
@@ -1,30 +1,24 @@
+use rand::random;
+
 use crate::{
     colour::Colour,
     hit::Hit,
     light::Light,
-    object::Object,
-    photonmap::{Interaction, PhotonMap},
+    medium::Medium,
+    object::{volume::Volume, Object},
+    photonmap::PhotonMap,
     ray::Ray,
     Vertex,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Scene {
     pub object_list: Vec<Box<dyn Object + Send + Sync>>,
     pub light_list: Vec<Box<dyn Light + Send + Sync>>,
 }
 
-impl Default for Scene {
-    fn default() -> Self {
-        Self {
-            object_list: Vec::default(),
-            light_list: Vec::default(),
-        }
-    }
-}
-
 impl Scene {
-    pub fn trace(&self, ray: &Ray) -> Option<Hit> {
+    pub fn trace(&self, ray: &Ray) -> Option<Hit<'_>> {
         // find hits along the given ray with the scene
         let mut best_hit = None;
 
@@ -47,33 +41,39 @@ impl Scene {
 
     pub fn select_first(hits: Vec<Hit>) -> Option<Hit> {
         // select the front-most hit (the hit with the lowest t value) along the ray
+        // hits on partially transparent materials are stochastically skipped
         let mut result: Option<Hit> = None;
         for hit in hits {
+            if hit.t < 0. {
+                continue;
+            }
             if let Some(h) = &result {
-                if hit.t < h.t && hit.t >= 0. {
+                if hit.t < h.t && Self::is_opaque(&hit) {
                     result = Some(hit);
                 }
-            } else if hit.t >= 0. {
+            } else if Self::is_opaque(&hit) {
                 result = Some(hit);
             }
         }
         result
     }
+
+    fn is_opaque(hit: &Hit) -> bool {
+        let opacity = hit.material.opacity(hit);
+        opacity >= 1. || random::<f32>() < opacity
+    }
 }
 
 impl Scene {
     pub fn shadow_trace(&self, ray: &Ray, limit: f32) -> bool {
         for object in &self.object_list {
             if let Some(hit) = Self::select_first(object.intersection(ray)) {
-                if hit.t > 0.00001 && hit.t < limit {
-                    match hit.material.interact(&hit) {
-                        Interaction::Transmitted { .. } => continue,
-                        _ => return true,
-                    }
+                if hit.t > 0.00001 && hit.t < limit && !hit.material.transmits_shadow(&hit) {
+                    return true;
                 }
             }
         }
-        return false;
+        false
     }
 
     pub fn raytrace(
@@ -108,6 +108,11 @@ impl Scene {
         self.object_list.push(Box::new(object));
     }
 
+    pub fn add_medium<M: Medium + 'static>(&mut self, medium: M) {
+        // fill the whole scene with a participating medium such as fog
+        self.object_list.push(Box::new(Volume::global(medium)));
+    }
+
     pub fn add_light<L: Light + Send + Sync + 'static>(&mut self, light: L) {
         self.light_list.push(Box::new(light));
     }
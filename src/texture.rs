@@ -0,0 +1,17 @@
+use std::fmt::Debug;
+
+use crate::{colour::Colour, hit::Hit};
+
+pub mod checker;
+
+pub trait Texture: Debug + Send + Sync {
+    // look up the texture value at the hit
+    fn value(&self, hit: &Hit) -> Colour;
+}
+
+impl Texture for Colour {
+    fn value(&self, _hit: &Hit) -> Colour {
+        // a plain colour is a constant texture
+        *self
+    }
+}
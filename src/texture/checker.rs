@@ -0,0 +1,28 @@
+use super::Texture;
+use crate::{colour::Colour, hit::Hit};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Checker {
+    odd: Colour,
+    even: Colour,
+    scale: f32,
+}
+
+impl Checker {
+    pub fn new(odd: Colour, even: Colour, scale: f32) -> Self {
+        Checker { odd, even, scale }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, hit: &Hit) -> Colour {
+        // solid 3D checkerboard, so no surface parameterisation is needed
+        let p = hit.position / self.scale;
+        let sum = p.x.floor() as i32 + p.y.floor() as i32 + p.z.floor() as i32;
+        if sum % 2 == 0 {
+            self.even
+        } else {
+            self.odd
+        }
+    }
+}
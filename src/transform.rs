@@ -0,0 +1,124 @@
+use glam::{Affine3A, Mat3A, Vec2, Vec3A};
+
+use crate::{hit::Hit, material::Material, object::Object, ray::Ray, Vertex};
+
+// normals use the inverse transpose so they stay perpendicular under non-uniform scales
+pub(crate) fn normal_matrix(t: Affine3A) -> Mat3A {
+    t.matrix3.inverse().transpose()
+}
+
+// a surface hit found in object space, with the outward normal
+#[derive(Copy, Clone, Debug)]
+pub struct LocalHit {
+    pub t: f32,
+    pub normal: Vec3A,
+    pub tangent: Vec3A,
+    pub uv: Vec2,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    to_world: Affine3A,
+    to_local: Affine3A,
+    normal_matrix: Mat3A,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::new(Affine3A::IDENTITY)
+    }
+}
+
+impl Transform {
+    pub fn new(to_world: Affine3A) -> Self {
+        let to_local = to_world.inverse();
+        Self {
+            to_world,
+            to_local,
+            normal_matrix: normal_matrix(to_world),
+        }
+    }
+
+    pub fn apply(&mut self, t: Affine3A) {
+        // apply a further transformation after the existing one
+        *self = Self::new(t * self.to_world);
+    }
+
+    pub fn to_world(&self) -> Affine3A {
+        self.to_world
+    }
+
+    pub fn ray_to_local(&self, ray: &Ray) -> Ray {
+        // the direction is left unnormalised so t is the same in both spaces
+        ray.spawn(
+            self.to_local.transform_point3a(ray.position),
+            self.to_local.transform_vector3a(ray.direction),
+        )
+    }
+
+    pub fn point_to_world(&self, p: Vertex) -> Vertex {
+        self.to_world.transform_point3a(p)
+    }
+
+    pub fn vector_to_world(&self, v: Vec3A) -> Vec3A {
+        self.to_world.transform_vector3a(v)
+    }
+
+    pub fn normal_to_world(&self, n: Vec3A) -> Vec3A {
+        (self.normal_matrix * n).normalize()
+    }
+
+    pub fn hits_to_world<'a>(
+        &self,
+        ray: &Ray,
+        mut hits: Vec<LocalHit>,
+        object: &'a (dyn Object + Send + Sync),
+        material: &'a (dyn Material + Send + Sync),
+    ) -> Vec<Hit<'a>> {
+        // order the hits along the ray so entry and exit pairs line up for csg
+        hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+        hits.into_iter()
+            .map(|h| {
+                let normal = self.normal_to_world(h.normal);
+                let entering = normal.dot(ray.direction) < 0.;
+                let tangent = self.vector_to_world(h.tangent);
+                let tangent = (tangent - tangent.dot(normal) * normal)
+                    .try_normalize()
+                    .unwrap_or_else(|| normal.any_orthonormal_vector());
+                Hit {
+                    t: h.t,
+                    entering,
+                    object_hit: object,
+                    material,
+                    position: ray.position + h.t * ray.direction,
+                    // flip normals to face the ray
+                    normal: if entering { normal } else { -normal },
+                    tangent,
+                    uv: h.uv,
+                    colour: None,
+                    incident: ray.clone(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn object_hits_to_world<'a>(
+        &self,
+        ray: &Ray,
+        hits: Vec<Hit<'a>>,
+        object: &'a (dyn Object + Send + Sync),
+    ) -> Vec<Hit<'a>> {
+        // hits on a wrapped object, found with the local ray, which keeps the same t
+        hits.into_iter()
+            .map(|hit| Hit {
+                // materials that trace the object again need it in world space
+                object_hit: object,
+                position: ray.position + hit.t * ray.direction,
+                normal: self.normal_to_world(hit.normal),
+                tangent: self.vector_to_world(hit.tangent).normalize(),
+                incident: ray.clone(),
+                ..hit
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,77 @@
+use glam::{Affine3A, Vec3A};
+use raytracer::{
+    animation::{Animation, Interpolation, Track},
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::sphere::Sphere,
+    ray::Ray,
+    scene::Scene,
+    Vertex,
+};
+
+fn still_camera() -> Animation {
+    Animation::new(
+        Track::constant(Vertex::ZERO),
+        Track::constant(Vertex::new(0., 0., 1.)),
+        Track::constant(Vec3A::Y),
+    )
+}
+
+fn scene_with_spheres() -> Scene {
+    let mut scene = Scene::default();
+    for x in [-2., 2.] {
+        scene.add_object(Sphere::new(
+            Vertex::new(x, 0., 5.),
+            1.,
+            Diffuse::new(Colour::from_rgb(1., 1., 1.)),
+        ));
+    }
+    scene
+}
+
+fn distance_to(scene: &Scene, x: f32) -> Option<f32> {
+    scene
+        .trace(&Ray::new(Vertex::new(x, 0., 0.), Vec3A::Z))
+        .map(|h| h.t)
+}
+
+#[test]
+fn empty_tracks_have_no_value() {
+    let track = Track::<f32>::new(Interpolation::Cubic);
+    assert_eq!(track.at(0.), None);
+    assert_eq!(track.at(3.), None);
+
+    let mut camera = still_camera();
+    assert!(camera.camera_at(1.).is_some());
+    camera.camera_up = Track::new(Interpolation::Linear);
+    assert!(camera.camera_at(1.).is_none());
+}
+
+#[test]
+fn keyed_tracks_hold_their_ends() {
+    let mut track = Track::new(Interpolation::Linear);
+    track.add_key(1., 2.);
+    track.add_key(3., 6.);
+    assert_eq!(track.at(0.), Some(2.));
+    assert_eq!(track.at(2.), Some(4.));
+    assert_eq!(track.at(5.), Some(6.));
+}
+
+#[test]
+fn applying_skips_tracks_without_keys() {
+    // the first sphere has no keys and stays put while the second moves back by one
+    let mut animation = still_camera();
+    animation.animate_object(0, Track::new(Interpolation::Linear));
+    animation.animate_object(
+        1,
+        Track::constant(Affine3A::from_translation([0., 0., 1.].into())),
+    );
+    let mut scene = scene_with_spheres();
+    animation.apply(&mut scene, 1.);
+
+    let near = distance_to(&scene, -2.).unwrap();
+    let far = distance_to(&scene, 2.).unwrap();
+    assert!((near - 4.).abs() < 0.0001, "{}", near);
+    assert!((far - 5.).abs() < 0.0001, "{}", far);
+    assert!(!animation.scene_changes(0., 1.));
+}
@@ -0,0 +1,54 @@
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    light::point::Point,
+    material::{anisotropic::Anisotropic, diffuse::Diffuse},
+    object::{plane::Plane, sphere::Sphere},
+    photonmap::PhotonMap,
+    ray::Ray,
+    scene::Scene,
+    Vertex,
+};
+
+// a brushed floor under a point light, seen from where it mirrors the light
+fn floor_scene(blocked: bool) -> Scene {
+    let mut scene = Scene::default();
+    scene.add_object(Plane::new(
+        Vec3A::Y,
+        Vertex::ZERO,
+        Anisotropic::new(Colour::from_rgb(0.9, 0.9, 0.9), 0.2, 0.4, 0.),
+    ));
+    scene.add_light(Point::new(
+        Vertex::new(0., 1., 1.),
+        Colour::from_rgb(1., 1., 1.),
+    ));
+    if blocked {
+        scene.add_object(Sphere::new(
+            Vertex::new(0., 0.5, 0.5),
+            0.1,
+            Diffuse::new(Colour::from_rgb(0., 0., 0.)),
+        ));
+    }
+    scene
+}
+
+fn brightness(scene: &Scene) -> f32 {
+    let empty = Scene::default();
+    let pmap = PhotonMap::build(&empty);
+    let viewer = Vertex::new(0., 1., -1.);
+    let ray = Ray::new(viewer, -viewer.normalize());
+    let (colour, _) = scene.raytrace(ray, 4, viewer, &pmap);
+    (colour.r + colour.g + colour.b) / 3.
+}
+
+#[test]
+fn point_lights_give_a_highlight() {
+    let lit = brightness(&floor_scene(false));
+    assert!(lit > 0.5, "{}", lit);
+}
+
+#[test]
+fn highlights_are_shadowed() {
+    let shadowed = brightness(&floor_scene(true));
+    assert!(shadowed < 0.001, "{}", shadowed);
+}
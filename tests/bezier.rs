@@ -0,0 +1,52 @@
+use glam::{Vec2, Vec3A};
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{bezier::Bezier, Object},
+    ray::Ray,
+    Vertex,
+};
+
+// a patch over x and y from -1 to 1, with its depth given along u
+fn patch(depth: impl Fn(f32) -> f32) -> Bezier {
+    let net = [0, 1, 2, 3].map(|i| {
+        let u = i as f32 / 3.;
+        [0, 1, 2, 3].map(|j| {
+            let v = j as f32 / 3.;
+            Vertex::new(2. * u - 1., 2. * v - 1., depth(u))
+        })
+    });
+    Bezier::from_nets(vec![net], Diffuse::new(Colour::from_rgb(1., 1., 1.)))
+}
+
+fn along_z(x: f32, y: f32) -> Ray {
+    Ray::new(Vertex::new(x, y, 0.), Vec3A::Z)
+}
+
+#[test]
+fn flat_patches_are_hit_once_with_their_parameters() {
+    let flat = patch(|_| 5.);
+    let hits = flat.intersection(&along_z(0.5, -0.5));
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].t - 5.).abs() < 0.0001, "{}", hits[0].t);
+    assert!((hits[0].uv - Vec2::new(0.75, 0.25)).length() < 0.0001);
+    // the normal faces along du x dv, so the ray comes in from behind
+    assert!(!hits[0].entering);
+    assert!(hits[0].normal.abs_diff_eq(-Vec3A::Z, 0.0001));
+    assert!(flat.intersection(&along_z(1.2, 0.)).is_empty());
+}
+
+#[test]
+fn sloping_patches_follow_their_control_points() {
+    // control points evenly spaced along a line give that line back
+    let slope = patch(|u| 5. + u);
+    let hits = slope.intersection(&along_z(0., 0.3));
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].t - 5.5).abs() < 0.0001, "{}", hits[0].t);
+    let normal = Vec3A::new(1., 0., -2.).normalize();
+    assert!(
+        hits[0].normal.abs_diff_eq(normal, 0.0001),
+        "{}",
+        hits[0].normal
+    );
+}
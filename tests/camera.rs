@@ -0,0 +1,54 @@
+use glam::Vec3A;
+use raytracer::{
+    camera::{orthographic::Orthographic, Camera},
+    Vertex,
+};
+
+// looking along +z from the origin, 4 units across and 2 high over 4 by 2 pixels
+fn orthographic() -> Orthographic {
+    Orthographic::new(Vertex::ZERO, Vertex::new(0., 0., 1.), Vec3A::Y, 4., 4, 2, 1)
+}
+
+#[test]
+fn orthographic_rays_are_parallel() {
+    let camera = orthographic();
+    for (x, y) in [(0, 0), (3, 1), (1, 0)] {
+        let ray = camera.get_ray_pixel(x, y);
+        assert!(
+            ray.direction.abs_diff_eq(Vec3A::Z, 0.0001),
+            "{}",
+            ray.direction
+        );
+    }
+}
+
+#[test]
+fn orthographic_rays_start_within_their_pixel() {
+    // pixels are one unit square on the image plane, counted from the bottom left with x
+    // across and y up
+    let camera = orthographic();
+    for (x, y) in [(0, 0), (3, 1), (2, 0)] {
+        for _ in 0..100 {
+            let p = camera.get_ray_pixel(x, y).position;
+            let (left, bottom) = (x as f32 - 2., y as f32 - 1.);
+            assert!(p.x >= left && p.x <= left + 1., "{} for {:?}", p, (x, y));
+            assert!(
+                p.y >= bottom && p.y <= bottom + 1.,
+                "{} for {:?}",
+                p,
+                (x, y)
+            );
+            assert!(p.z.abs() < 0.0001);
+        }
+    }
+}
+
+#[test]
+fn orthographic_rays_sample_the_shutter() {
+    let camera = orthographic().with_shutter(1., 1.5);
+    for _ in 0..100 {
+        let time = camera.get_ray_pixel(1, 1).time;
+        assert!((1. ..=1.5).contains(&time), "{}", time);
+    }
+    assert_eq!(orthographic().get_ray_pixel(1, 1).time, 0.);
+}
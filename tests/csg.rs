@@ -0,0 +1,294 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use glam::{Affine3A, Vec3, Vec3A};
+use raytracer::{
+    aabb::Aabb,
+    colour::Colour,
+    hit::Hit,
+    material::diffuse::Diffuse,
+    object::{
+        axisbox::AxisBox,
+        csg::{Csg, CsgBuilder, Op},
+        plane::Plane,
+        sphere::Sphere,
+        triangle::Triangle,
+        Object,
+    },
+    ray::Ray,
+    Vertex,
+};
+
+fn white() -> Diffuse {
+    Diffuse::new(Colour::from_rgb(1., 1., 1.))
+}
+
+fn sphere(x: f32) -> Sphere {
+    Sphere::new(Vertex::new(x, 0., 5.), 1., white())
+}
+
+fn along_x(start: f32) -> Ray {
+    Ray::new(Vertex::new(start, 0., 5.), Vec3A::X)
+}
+
+// the t, entering flag and normal of each hit
+fn summary(hits: &[Hit]) -> Vec<(f32, bool, Vec3A)> {
+    hits.iter().map(|h| (h.t, h.entering, h.normal)).collect()
+}
+
+fn assert_hits(hits: &[Hit], expected: &[(f32, bool, Vec3A)]) {
+    assert_eq!(hits.len(), expected.len(), "{:?}", summary(hits));
+    for (hit, &(t, entering, normal)) in hits.iter().zip(expected) {
+        assert!((hit.t - t).abs() < 0.0001, "{:?}", summary(hits));
+        assert_eq!(hit.entering, entering, "{:?}", summary(hits));
+        assert!(
+            (hit.normal - normal).length() < 0.0001,
+            "{:?}",
+            summary(hits)
+        );
+    }
+}
+
+#[test]
+fn union_of_spheres_keeps_outer_surfaces() {
+    let csg = Csg::new_branch(sphere(-0.5), sphere(0.5), Op::Union);
+    assert_hits(
+        &csg.intersection(&along_x(-5.)),
+        &[(3.5, true, -Vec3A::X), (6.5, false, -Vec3A::X)],
+    );
+}
+
+#[test]
+fn intersection_of_spheres_is_a_lens() {
+    let csg = Csg::new_branch(sphere(-0.5), sphere(0.5), Op::Intersection);
+    assert_hits(
+        &csg.intersection(&along_x(-5.)),
+        &[(4.5, true, -Vec3A::X), (5.5, false, -Vec3A::X)],
+    );
+}
+
+#[test]
+fn difference_flips_subtracted_surfaces() {
+    // the box cuts away the right half of the sphere
+    let cutter = AxisBox::new(Vertex::new(0., -2., 3.), Vertex::new(2., 2., 7.), white());
+    let csg = Csg::new_branch(sphere(0.), cutter, Op::Difference);
+    assert_hits(
+        &csg.intersection(&along_x(-5.)),
+        &[(4., true, -Vec3A::X), (5., false, -Vec3A::X)],
+    );
+
+    // from the other side the box's face becomes the way in
+    let hits = csg.intersection(&Ray::new(Vertex::new(5., 0., 5.), -Vec3A::X));
+    assert_hits(&hits, &[(5., true, Vec3A::X), (6., false, Vec3A::X)]);
+}
+
+#[test]
+fn difference_of_box_and_sphere() {
+    let block = AxisBox::new(Vertex::new(-2., -1., 4.), Vertex::new(2., 1., 6.), white());
+    let csg = Csg::new_branch(block, sphere(0.), Op::Difference);
+    assert_hits(
+        &csg.intersection(&along_x(-5.)),
+        &[
+            (3., true, -Vec3A::X),
+            (4., false, -Vec3A::X),
+            (6., true, -Vec3A::X),
+            (7., false, -Vec3A::X),
+        ],
+    );
+}
+
+#[test]
+fn rays_starting_inside_stay_paired() {
+    // starting inside the left sphere, its entry is behind the ray
+    let csg = Csg::new_branch(sphere(-0.5), sphere(0.5), Op::Difference);
+    let hits = csg.intersection(&along_x(-0.5));
+    assert_hits(&hits, &[(-1., true, -Vec3A::X), (0., false, -Vec3A::X)]);
+}
+
+#[test]
+fn single_hit_triangles_form_a_solid() {
+    // a tetrahedron with its corners wound to face outwards
+    let [a, b, c, d] = [
+        Vertex::new(-1., -1., 4.),
+        Vertex::new(1., -1., 4.),
+        Vertex::new(0., -1., 6.),
+        Vertex::new(0., 1., 5.),
+    ];
+    let tetrahedron = || {
+        Csg::new_branch(
+            Csg::new_branch(
+                Triangle::new([a, b, c], white()),
+                Triangle::new([a, d, b], white()),
+                Op::Union,
+            ),
+            Csg::new_branch(
+                Triangle::new([b, d, c], white()),
+                Triangle::new([c, d, a], white()),
+                Op::Union,
+            ),
+            Op::Union,
+        )
+    };
+
+    // triangles only report hits in front of the ray, so from inside there is just the exit
+    let inside = Ray::new(Vertex::new(0., -0.5, 5.), Vec3A::Y);
+    let solid = tetrahedron();
+    let hits = solid.intersection(&inside);
+    assert_eq!(
+        summary(&hits).iter().map(|h| h.1).collect::<Vec<_>>(),
+        [false]
+    );
+
+    // cutting a sphere with it keeps the part of the sphere inside the tetrahedron
+    let through = Ray::new(Vertex::new(0., -5., 5.), Vec3A::Y);
+    let big = Sphere::new(Vertex::new(0., 0., 5.), 0.8, white());
+    let csg = Csg::new_branch(tetrahedron(), big, Op::Intersection);
+    let hits = csg.intersection(&through);
+    assert_hits(&hits, &[(4.2, true, -Vec3A::Y), (5.8, false, -Vec3A::Y)]);
+}
+
+#[test]
+fn plane_is_a_half_space() {
+    // everything behind the plane's normal is inside, leaving the lower half of the sphere
+    let floor = Plane::new(Vec3A::Y, Vertex::new(0., 0., 5.), white());
+    let csg = Csg::new_branch(sphere(0.), floor, Op::Intersection);
+    let down = Ray::new(Vertex::new(0., 5., 5.), -Vec3A::Y);
+    assert_hits(
+        &csg.intersection(&down),
+        &[(5., true, Vec3A::Y), (6., false, Vec3A::Y)],
+    );
+    let up = Ray::new(Vertex::new(0., -5., 5.), Vec3A::Y);
+    assert_hits(
+        &csg.intersection(&up),
+        &[(4., true, -Vec3A::Y), (5., false, -Vec3A::Y)],
+    );
+
+    // a union with the half space runs on from the sphere forever
+    let floor = Plane::new(Vec3A::Y, Vertex::new(0., 0., 5.), white());
+    let csg = Csg::new_branch(sphere(0.), floor, Op::Union);
+    assert_hits(&csg.intersection(&down), &[(4., true, Vec3A::Y)]);
+    assert_hits(&csg.intersection(&up), &[(6., false, -Vec3A::Y)]);
+}
+
+#[test]
+fn rays_parallel_to_a_plane_are_inside_or_out_all_along() {
+    // the ray runs beneath the plane without crossing it, so it is inside the whole way
+    let floor = || Plane::new(Vec3A::Y, Vertex::new(0., 2., 5.), white());
+    let csg = floor().intersect(sphere(0.));
+    assert_hits(
+        &csg.intersection(&along_x(-5.)),
+        &[(4., true, -Vec3A::X), (6., false, -Vec3A::X)],
+    );
+    let csg = floor().minus(sphere(0.));
+    assert_hits(
+        &csg.intersection(&along_x(-5.)),
+        &[(4., false, -Vec3A::X), (6., true, -Vec3A::X)],
+    );
+
+    // the same holds for a csg child that is itself a half space
+    let csg = floor().union(floor()).minus(sphere(0.));
+    assert_eq!(csg.intersection(&along_x(-5.)).len(), 2);
+
+    // above a plane the ray is outside all along
+    let ceiling = || Plane::new(Vec3A::Y, Vertex::new(0., -2., 5.), white());
+    assert!(ceiling()
+        .intersect(sphere(0.))
+        .intersection(&along_x(-5.))
+        .is_empty());
+    assert!(ceiling()
+        .minus(sphere(0.))
+        .intersection(&along_x(-5.))
+        .is_empty());
+}
+
+#[test]
+fn builder_flattens_into_nary_nodes() {
+    let csg = sphere(-1.5)
+        .union(sphere(-0.5))
+        .union(sphere(0.5))
+        .minus(sphere(1.5));
+    match &csg {
+        Csg::Branch {
+            children,
+            operation: Op::Difference,
+            ..
+        } => {
+            assert_eq!(children.len(), 2);
+            assert!(matches!(
+                &children[0],
+                Csg::Branch { children, operation: Op::Union, .. } if children.len() == 3
+            ));
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_hits(
+        &csg.intersection(&along_x(-5.)),
+        &[(2.5, true, -Vec3A::X), (5.5, false, -Vec3A::X)],
+    );
+
+    // an n-ary node gives the same surfaces as nesting pairs
+    let nary = Csg::new_nary(sphere(-1.), [sphere(0.), sphere(1.)], Op::Union);
+    let nested = Csg::new_branch(
+        Csg::new_branch(sphere(-1.), sphere(0.), Op::Union),
+        sphere(1.),
+        Op::Union,
+    );
+    assert_eq!(
+        summary(&nary.intersection(&along_x(-5.))),
+        summary(&nested.intersection(&along_x(-5.)))
+    );
+}
+
+// a sphere that counts how often it is intersected
+#[derive(Debug)]
+struct Counted {
+    sphere: Sphere,
+    calls: Arc<AtomicUsize>,
+}
+
+impl Object for Counted {
+    fn intersection(&self, ray: &Ray) -> Vec<Hit<'_>> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.sphere.intersection(ray)
+    }
+
+    fn apply_transform(&mut self, t: Affine3A) {
+        self.sphere.apply_transform(t);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.sphere.bounds()
+    }
+}
+
+#[test]
+fn children_outside_the_ray_are_skipped() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counted = || {
+        Csg::new_leaf(Box::new(Counted {
+            sphere: Sphere::new(Vertex::new(0., 3., 5.), 1., white()),
+            calls: calls.clone(),
+        }))
+    };
+
+    let union = sphere(0.).union(counted());
+    assert_eq!(union.intersection(&along_x(-5.)).len(), 2);
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    let up = Ray::new(Vertex::new(0., -5., 5.), Vec3A::Y);
+    assert_eq!(union.intersection(&up).len(), 4);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    // the bounds follow the object when it moves
+    let mut moved = sphere(0.).union(counted());
+    moved.apply_transform(Affine3A::from_translation(Vec3::new(0., -3., 0.)));
+    let hits = moved.intersection(&Ray::new(Vertex::new(0., -10., 5.), Vec3A::Y));
+    assert_eq!(hits.len(), 4);
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+    // an intersection that has already missed its first child stops there
+    let lens = Csg::new_branch(sphere(0.), counted(), Op::Intersection);
+    assert!(lens.intersection(&along_x(-5.)).is_empty());
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,66 @@
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{
+        curves::{Curve, CurveShape, Curves},
+        Object,
+    },
+    ray::Ray,
+    Vertex,
+};
+
+fn white() -> Diffuse {
+    Diffuse::new(Colour::from_rgb(1., 1., 1.))
+}
+
+// an s bend in the z = 5 plane, passing through (0, 0, 5) halfway along where it is split
+fn s_bend(shape: CurveShape) -> Curves {
+    let curve = Curve::new(
+        [
+            Vertex::new(-1.5, 0., 5.),
+            Vertex::new(-0.5, 1., 5.),
+            Vertex::new(0.5, -1., 5.),
+            Vertex::new(1.5, 0., 5.),
+        ],
+        [0.2; 4],
+    );
+    Curves::new(vec![curve], shape, white())
+}
+
+fn hit_distances(curves: &Curves, ray: &Ray) -> Vec<f32> {
+    curves.intersection(ray).iter().map(|h| h.t).collect()
+}
+
+#[test]
+fn cylinders_are_hit_on_both_sides_of_the_tube() {
+    let ray = Ray::new(Vertex::new(-1.5, 0., 0.), Vec3A::Z);
+    let t = hit_distances(&s_bend(CurveShape::Cylinder), &ray);
+    assert_eq!(t.len(), 2, "{t:?}");
+    assert!(
+        (t[0] - 4.9).abs() < 0.01 && (t[1] - 5.1).abs() < 0.01,
+        "{t:?}"
+    );
+}
+
+#[test]
+fn split_points_are_only_hit_once() {
+    let ray = Ray::new(Vertex::new(0., 0., 0.), Vec3A::Z);
+
+    let t = hit_distances(&s_bend(CurveShape::Cylinder), &ray);
+    assert_eq!(t.len(), 2, "{t:?}");
+    assert!(
+        (t[0] - 4.9).abs() < 0.01 && (t[1] - 5.1).abs() < 0.01,
+        "{t:?}"
+    );
+
+    let t = hit_distances(&s_bend(CurveShape::Ribbon), &ray);
+    assert_eq!(t.len(), 1, "{t:?}");
+    assert!((t[0] - 5.).abs() < 0.01, "{t:?}");
+}
+
+#[test]
+fn rays_beside_the_curve_miss() {
+    let ray = Ray::new(Vertex::new(0., 0.5, 0.), Vec3A::Z);
+    assert!(hit_distances(&s_bend(CurveShape::Ribbon), &ray).is_empty());
+}
@@ -0,0 +1,168 @@
+use glam::{Affine3A, Quat, Vec3, Vec3A};
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{quadratic::Quadratic, sphere::Sphere, Object},
+    ray::Ray,
+    Vertex,
+};
+
+// an ellipsoid with the given semi-axes, rotated and then moved to its centre
+struct Ellipsoid {
+    axes: Vec3A,
+    rotation: Quat,
+    centre: Vertex,
+}
+
+impl Ellipsoid {
+    fn transform(&self) -> Affine3A {
+        Affine3A::from_scale_rotation_translation(
+            self.axes.into(),
+            self.rotation,
+            self.centre.into(),
+        )
+    }
+
+    // the hits along the ray as (t, outward normal), solved directly in the ellipsoid's frame
+    fn hits(&self, ray: &Ray) -> Vec<(f32, Vec3A)> {
+        let inverse = self.rotation.inverse();
+        let q = Vec3A::from(inverse * Vec3::from(ray.position - self.centre)) / self.axes;
+        let v = Vec3A::from(inverse * Vec3::from(ray.direction)) / self.axes;
+        let a = v.dot(v);
+        let b = 2. * q.dot(v);
+        let c = q.dot(q) - 1.;
+        let disc = b * b - 4. * a * c;
+        if disc < 0. {
+            return Vec::new();
+        }
+        [-1., 1.]
+            .map(|s| {
+                let t = (-b + s * disc.sqrt()) / (2. * a);
+                let local = (q + t * v) / self.axes;
+                let normal = Vec3A::from(self.rotation * Vec3::from(local)).normalize();
+                (t, normal)
+            })
+            .to_vec()
+    }
+}
+
+fn ellipsoids() -> Vec<Ellipsoid> {
+    vec![
+        Ellipsoid {
+            axes: Vec3A::new(2., 1., 0.5),
+            rotation: Quat::IDENTITY,
+            centre: Vertex::new(0., 0., 5.),
+        },
+        Ellipsoid {
+            axes: Vec3A::new(0.5, 3., 1.5),
+            rotation: Quat::from_euler(glam::EulerRot::XYZ, 0.3, -1.1, 0.7),
+            centre: Vertex::new(-1., 2., 8.),
+        },
+    ]
+}
+
+fn rays() -> Vec<Ray> {
+    // a fan of rays from points around the origin, some missing and some starting inside
+    let mut rays = Vec::new();
+    for i in 0..20 {
+        for j in 0..20 {
+            let direction = Vec3A::new(i as f32 * 0.05 - 0.5, j as f32 * 0.05 - 0.5, 1.);
+            rays.push(Ray::new(Vertex::new(0.1, -0.2, 0.), direction.normalize()));
+            rays.push(Ray::new(Vertex::new(-1., 2., 8.), direction));
+        }
+    }
+    rays
+}
+
+fn check(object: &dyn Object, ellipsoid: &Ellipsoid) {
+    let mut hits = 0;
+    for ray in rays() {
+        let expected = ellipsoid.hits(&ray);
+        let found = object.intersection(&ray);
+        assert_eq!(found.len(), expected.len(), "hit count for {:?}", ray);
+        for (hit, (t, outward)) in found.iter().zip(expected) {
+            assert!((hit.t - t).abs() < 0.0001, "t {} should be {}", hit.t, t);
+            let position = ray.position + t * ray.direction;
+            assert!((hit.position - position).length() < 0.0001);
+
+            // normals face the ray, and entering hits are the ones against the outward normal
+            let entering = outward.dot(ray.direction) < 0.;
+            assert_eq!(hit.entering, entering);
+            let facing = if entering { outward } else { -outward };
+            assert!(
+                (hit.normal - facing).length() < 0.001,
+                "normal {:?} should be {:?}",
+                hit.normal,
+                facing
+            );
+            hits += 1;
+        }
+    }
+    assert!(hits > 20, "only {} hits were tested", hits);
+}
+
+fn white() -> Diffuse {
+    Diffuse::new(Colour::from_rgb(1., 1., 1.))
+}
+
+#[test]
+fn scaled_sphere_is_an_ellipsoid() {
+    for ellipsoid in ellipsoids() {
+        let mut sphere = Sphere::new(Vertex::ZERO, 1., white());
+        sphere.apply_transform(ellipsoid.transform());
+        check(&sphere, &ellipsoid);
+    }
+}
+
+#[test]
+fn sphere_radius_and_centre_compose_with_transforms() {
+    let ellipsoid = Ellipsoid {
+        axes: Vec3A::new(1., 2., 1.),
+        rotation: Quat::IDENTITY,
+        centre: Vertex::new(0.5, 0., 6.),
+    };
+    let mut sphere = Sphere::new(Vertex::new(0.25, 0., 3.), 0.5, white());
+    sphere.apply_transform(Affine3A::from_scale(Vec3::new(2., 4., 2.)));
+    check(&sphere, &ellipsoid);
+}
+
+#[test]
+fn transformed_quadratic_is_an_ellipsoid() {
+    for ellipsoid in ellipsoids() {
+        // x^2 + y^2 + z^2 - 1 = 0
+        let mut quadratic = Quadratic::new(1., 0., 0., 0., 1., 0., 0., 1., 0., -1., white());
+        quadratic.apply_transform(ellipsoid.transform());
+        check(&quadratic, &ellipsoid);
+    }
+}
+
+#[test]
+fn quadratic_translation_is_not_inverted() {
+    let ellipsoid = Ellipsoid {
+        axes: Vec3A::ONE,
+        rotation: Quat::IDENTITY,
+        centre: Vertex::new(1., 0.5, 6.),
+    };
+    let mut quadratic = Quadratic::new(1., 0., 0., 0., 1., 0., 0., 1., 0., -1., white());
+    quadratic.apply_transform(Affine3A::from_translation(Vec3::new(1., 0.5, 6.)));
+    check(&quadratic, &ellipsoid);
+}
+
+#[test]
+fn quadratic_ellipsoid_constructor() {
+    let ellipsoid = Ellipsoid {
+        axes: Vec3A::new(2., 1., 0.5),
+        rotation: Quat::IDENTITY,
+        centre: Vertex::new(0., 0., 5.),
+    };
+    let quadratic = Quadratic::ellipsoid(ellipsoid.centre, ellipsoid.axes, white());
+    check(&quadratic, &ellipsoid);
+}
+
+#[test]
+fn rays_along_a_paraboloid_axis_hit_its_vertex() {
+    let paraboloid = Quadratic::paraboloid(Vertex::new(0., 0., 5.), Vec3A::Y, 1., white());
+    let hits = paraboloid.intersection(&Ray::new(Vertex::new(0., 3., 5.), -Vec3A::Y));
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].t - 3.).abs() < 0.0001, "{}", hits[0].t);
+}
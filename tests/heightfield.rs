@@ -0,0 +1,56 @@
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{heightfield::Heightfield, Object},
+    ray::Ray,
+    Vertex,
+};
+
+// a 2 by 2 grid over x and z from 0 to 2, up to a height of 1
+fn grid(heights: Vec<f32>) -> Heightfield {
+    Heightfield::from_values(
+        [2, 2],
+        heights,
+        Vertex::ZERO,
+        Vertex::new(2., 1., 2.),
+        Diffuse::new(Colour::from_rgb(1., 1., 1.)),
+    )
+}
+
+fn down(x: f32, z: f32) -> Ray {
+    Ray::new(Vertex::new(x, 5., z), -Vec3A::Y)
+}
+
+#[test]
+fn flat_fields_are_hit_at_their_height() {
+    let field = grid(vec![0.5; 4]);
+    let hits = field.intersection(&down(1.3, 0.4));
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].t - 4.5).abs() < 0.0001, "{}", hits[0].t);
+    assert!(hits[0].entering);
+    assert!(hits[0].normal.abs_diff_eq(Vec3A::Y, 0.0001));
+    // outside the grid
+    assert!(field.intersection(&down(2.5, 1.)).is_empty());
+}
+
+#[test]
+fn sloping_fields_tilt_their_normals() {
+    // rising from 0 at x = 0 to 1 at x = 2
+    let field = grid(vec![0., 1., 0., 1.]);
+    let hits = field.intersection(&down(1., 1.5));
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].t - 4.5).abs() < 0.0001, "{}", hits[0].t);
+    let slope = Vec3A::new(-0.5, 1., 0.).normalize();
+    assert!(
+        hits[0].normal.abs_diff_eq(slope, 0.0001),
+        "{}",
+        hits[0].normal
+    );
+
+    // a level ray from the low side meets the slope where it has risen to the ray's height
+    let side = Ray::new(Vertex::new(-1., 0.25, 1.), Vec3A::X);
+    let hits = field.intersection(&side);
+    assert!(!hits.is_empty());
+    assert!((hits[0].t - 1.5).abs() < 0.0001, "{}", hits[0].t);
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use glam::{Affine3A, Vec3, Vec3A};
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{instance::Instance, sphere::Sphere, Object},
+    ray::Ray,
+    Vertex,
+};
+
+fn unit_sphere() -> Arc<dyn Object + Send + Sync> {
+    Arc::new(Sphere::new(
+        Vertex::ZERO,
+        1.,
+        Diffuse::new(Colour::from_rgb(1., 1., 1.)),
+    ))
+}
+
+fn along_z(x: f32) -> Ray {
+    Ray::new(Vertex::new(x, 0., 0.), Vec3A::Z)
+}
+
+#[test]
+fn instances_place_one_shared_object() {
+    let shared = unit_sphere();
+    let near = Instance::new(
+        shared.clone(),
+        Affine3A::from_translation(Vec3::new(0., 0., 5.)),
+    );
+    let far = Instance::new(
+        shared,
+        Affine3A::from_scale_rotation_translation(
+            Vec3::splat(2.),
+            Default::default(),
+            Vec3::new(3., 0., 10.),
+        ),
+    );
+
+    let mut hits = near.intersection(&along_z(0.));
+    hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+    assert_eq!(hits.iter().map(|h| h.t).collect::<Vec<_>>(), [4., 6.]);
+
+    // scaling the placement scales the object but leaves its normals unit length
+    let mut hits = far.intersection(&along_z(3.));
+    hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+    assert_eq!(hits.len(), 2);
+    assert!((hits[0].t - 8.).abs() < 0.0001 && (hits[1].t - 12.).abs() < 0.0001);
+    assert!(hits[0].normal.abs_diff_eq(-Vec3A::Z, 0.0001));
+    assert!((hits[0].position - Vertex::new(3., 0., 8.)).length() < 0.0001);
+
+    // each placement only holds its own copy
+    assert!(near.intersection(&along_z(3.)).is_empty());
+    assert!(far.intersection(&along_z(0.)).is_empty());
+}
+
+#[test]
+fn instances_can_change_the_material() {
+    let red = Diffuse::new(Colour::from_rgb(1., 0., 0.));
+    let tinted = Instance::with_material(
+        unit_sphere(),
+        Affine3A::from_translation(Vec3::new(0., 0., 5.)),
+        red,
+    );
+    let hit = tinted.intersection(&along_z(0.)).remove(0);
+    assert_eq!(format!("{:?}", hit.material), format!("{:?}", red));
+}
@@ -0,0 +1,67 @@
+use std::f32::consts::PI;
+
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    light::{point::Point, Light},
+    material::measured::Measured,
+    object::plane::Plane,
+    photonmap::PhotonMap,
+    ray::Ray,
+    scene::Scene,
+    Vertex,
+};
+
+// a table the size of a MERL file holding the same value everywhere
+fn constant(albedo: [f32; 3]) -> Measured {
+    Measured::from_values(vec![albedo.map(|a| a / PI); 90 * 90 * 180])
+}
+
+#[test]
+fn constant_tables_are_lambertian() {
+    let measured = constant([0.8, 0.5, 0.2]);
+    let directions = [
+        Vec3A::Z,
+        Vec3A::new(0.3, -0.2, 0.9).normalize(),
+        Vec3A::new(-0.7, 0.1, 0.2).normalize(),
+        Vec3A::new(0.1, 0.8, 0.05).normalize(),
+    ];
+    for wi in directions {
+        for wo in directions {
+            let f = measured.brdf(wi, wo);
+            assert!((f.r - 0.8 / PI).abs() < 0.0001, "{:?}", f);
+            assert!((f.g - 0.5 / PI).abs() < 0.0001, "{:?}", f);
+            assert!((f.b - 0.2 / PI).abs() < 0.0001, "{:?}", f);
+        }
+    }
+    // nothing is reflected from below the surface
+    let below = measured.brdf(-Vec3A::Z, Vec3A::Z);
+    assert_eq!((below.r, below.g, below.b), (0., 0., 0.));
+}
+
+#[test]
+fn constant_tables_shade_like_diffuse_surfaces() {
+    // a floor lit from above at an angle, with nothing else for sampled rays to reach
+    let light = Point::new(Vertex::new(1., 2., 0.), Colour::from_rgb(1., 1., 1.));
+    let mut scene = Scene::default();
+    scene.add_object(Plane::new(
+        Vec3A::Y,
+        Vertex::ZERO,
+        constant([0.8, 0.8, 0.8]),
+    ));
+    scene.add_light(light.clone());
+
+    let empty = Scene::default();
+    let pmap = PhotonMap::build(&empty);
+    let viewer = Vertex::new(0., 1., -1.);
+    let (colour, _) = scene.raytrace(Ray::new(viewer, -viewer), 4, viewer, &pmap);
+
+    let (ldir, _) = light.get_direction(Vertex::ZERO);
+    let expected = light.get_intensity(Vertex::ZERO).r * 0.8 / PI * ldir.y;
+    assert!(
+        (colour.r - expected).abs() < 0.0001,
+        "{} vs {}",
+        colour.r,
+        expected
+    );
+}
@@ -0,0 +1,55 @@
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{
+        metaballs::{Metaball, Metaballs},
+        Object,
+    },
+    ray::Ray,
+    Vertex,
+};
+
+fn blob(balls: Vec<Metaball>) -> Metaballs {
+    // a lone ball's field of (1 - r^2)^3 reaches an eighth at r^2 = 1/2
+    Metaballs::new(balls, 0.125, Diffuse::new(Colour::from_rgb(1., 1., 1.)))
+}
+
+fn along_x(y: f32) -> Ray {
+    Ray::new(Vertex::new(-5., y, 0.), Vec3A::X)
+}
+
+#[test]
+fn a_lone_ball_is_a_sphere() {
+    let ball = blob(vec![Metaball::new(Vertex::ZERO, 1., 1.)]);
+    let r = 0.5f32.sqrt();
+    let mut hits = ball.intersection(&along_x(0.));
+    hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+    assert_eq!(hits.len(), 2);
+    assert!((hits[0].t - (5. - r)).abs() < 0.0001, "{}", hits[0].t);
+    assert!((hits[1].t - (5. + r)).abs() < 0.0001, "{}", hits[1].t);
+    assert!(hits[0].entering && !hits[1].entering);
+    assert!(hits[0].normal.abs_diff_eq(-Vec3A::X, 0.0001));
+    assert!(ball.intersection(&along_x(0.75)).is_empty());
+}
+
+#[test]
+fn nearby_balls_merge() {
+    // apart each ball only reaches 0.71 from its centre, but together their fields bridge the
+    // gap between them
+    let pair = blob(vec![
+        Metaball::new(Vertex::new(-0.75, 0., 0.), 1., 1.),
+        Metaball::new(Vertex::new(0.75, 0., 0.), 1., 1.),
+    ]);
+    assert_eq!(pair.intersection(&along_x(0.)).len(), 2);
+    let between = Ray::new(Vertex::new(0., 5., 0.), -Vec3A::Y);
+    assert_eq!(pair.intersection(&between).len(), 2);
+
+    // far apart they stay separate
+    let apart = blob(vec![
+        Metaball::new(Vertex::new(-2., 0., 0.), 1., 1.),
+        Metaball::new(Vertex::new(2., 0., 0.), 1., 1.),
+    ]);
+    assert_eq!(apart.intersection(&along_x(0.)).len(), 4);
+    assert!(apart.intersection(&between).is_empty());
+}
@@ -0,0 +1,98 @@
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{torus::Torus, Object},
+    polynomial::{solve_cubic, solve_in_range, solve_quadratic, solve_quartic},
+    ray::Ray,
+    Vertex,
+};
+
+fn assert_roots(found: &[f64], expected: &[f64], tolerance: f64) {
+    assert_eq!(found.len(), expected.len(), "{:?}", found);
+    for (f, e) in found.iter().zip(expected) {
+        assert!((f - e).abs() < tolerance, "{:?} vs {:?}", found, expected);
+    }
+}
+
+#[test]
+fn quadratic_roots() {
+    assert_roots(&solve_quadratic(1., -3., 2.), &[1., 2.], 1e-12);
+    assert_roots(&solve_quadratic(1., -2., 1.), &[1., 1.], 1e-12);
+    assert_roots(&solve_quadratic(0., 2., -4.), &[2.], 1e-12);
+    assert_roots(&solve_quadratic(1., 0., 1.), &[], 0.);
+}
+
+#[test]
+fn cubic_roots() {
+    // (x - 1)(x - 2)(x - 3)
+    assert_roots(&solve_cubic(1., -6., 11., -6.), &[1., 2., 3.], 1e-9);
+    // (x - 2)(x^2 + 1) has one real root
+    assert_roots(&solve_cubic(1., -2., 1., -2.), &[2.], 1e-9);
+    // (x - 1)^2 (x - 2) with a double root
+    assert_roots(&solve_cubic(1., -4., 5., -2.), &[1., 1., 2.], 1e-6);
+}
+
+#[test]
+fn quartic_roots() {
+    // (x - 1)(x - 2)(x - 3)(x - 4)
+    assert_roots(
+        &solve_quartic(1., -10., 35., -50., 24.),
+        &[1., 2., 3., 4.],
+        1e-9,
+    );
+    // (x^2 - 1)^2 has two double roots
+    assert_roots(
+        &solve_quartic(1., 0., -2., 0., 1.),
+        &[-1., -1., 1., 1.],
+        1e-6,
+    );
+    // (x - 1)^2 (x - 2)(x - 3), with the double root away from the shift
+    assert_roots(
+        &solve_quartic(1., -7., 17., -17., 6.),
+        &[1., 1., 2., 3.],
+        1e-6,
+    );
+    // (x^2 + 1)(x^2 + 4) has no real roots
+    assert_roots(&solve_quartic(1., 0., 5., 0., 4.), &[], 0.);
+}
+
+#[test]
+fn roots_in_range() {
+    // (x - 1)(x - 2)(x - 3)(x - 4)(x - 5) between 1.5 and 4.5
+    let coefficients = [1., -15., 85., -225., 274., -120.];
+    assert_roots(
+        &solve_in_range(&coefficients, 1.5, 4.5),
+        &[2., 3., 4.],
+        1e-9,
+    );
+}
+
+#[test]
+fn torus_hits_both_sides_of_the_ring() {
+    // a ray through the middle of a ring of radius 2 and thickness 0.5 crosses the tube twice
+    let torus = Torus::new(
+        Vertex::ZERO,
+        Vec3A::Y,
+        2.,
+        0.5,
+        Diffuse::new(Colour::from_rgb(1., 1., 1.)),
+    );
+    let ray = Ray::new(Vertex::new(-5., 0., 0.), Vec3A::X);
+    let mut hits = torus.intersection(&ray);
+    hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+    let ts = hits.iter().map(|h| h.t).collect::<Vec<_>>();
+    let expected = [2.5, 3.5, 6.5, 7.5];
+    assert_eq!(ts.len(), 4, "{:?}", ts);
+    for (t, e) in ts.iter().zip(expected) {
+        assert!((t - e).abs() < 0.001, "{:?}", ts);
+    }
+    assert_eq!(
+        hits.iter().map(|h| h.entering).collect::<Vec<_>>(),
+        [true, false, true, false]
+    );
+
+    // one just grazing the top of the tube misses
+    let above = Ray::new(Vertex::new(-5., 0.51, 0.), Vec3A::X);
+    assert!(torus.intersection(&above).is_empty());
+}
@@ -0,0 +1,108 @@
+use glam::{Vec2, Vec3A};
+use raytracer::{
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{axisbox::AxisBox, cone::Cone, cylinder::Cylinder, disk::Disk, quad::Quad, Object},
+    ray::Ray,
+    Vertex,
+};
+
+fn white() -> Diffuse {
+    Diffuse::new(Colour::from_rgb(1., 1., 1.))
+}
+
+// the t and entering flag of each hit, in order along the ray
+fn summary(object: &dyn Object, ray: &Ray) -> Vec<(f32, bool)> {
+    let mut hits = object
+        .intersection(ray)
+        .into_iter()
+        .map(|h| (h.t, h.entering))
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+    hits
+}
+
+fn assert_hits(found: Vec<(f32, bool)>, expected: &[(f32, bool)]) {
+    assert_eq!(found.len(), expected.len(), "{:?}", found);
+    for (f, e) in found.iter().zip(expected) {
+        assert!((f.0 - e.0).abs() < 0.0001 && f.1 == e.1, "{:?}", found);
+    }
+}
+
+fn along_z(x: f32, y: f32) -> Ray {
+    Ray::new(Vertex::new(x, y, 0.), Vec3A::Z)
+}
+
+fn along_x(y: f32, z: f32) -> Ray {
+    Ray::new(Vertex::new(-5., y, z), Vec3A::X)
+}
+
+#[test]
+fn quads_are_hit_inside_their_edges() {
+    // facing along +z, so rays along +z come in from behind
+    let quad = Quad::new(
+        Vertex::new(-1., -1., 5.),
+        Vec3A::new(2., 0., 0.),
+        Vec3A::new(0., 2., 0.),
+        white(),
+    );
+    assert_hits(summary(&quad, &along_z(0., 0.)), &[(5., false)]);
+    let hit = quad.intersection(&along_z(0.5, -0.5)).remove(0);
+    assert!((hit.uv - Vec2::new(0.75, 0.25)).length() < 0.0001);
+    assert!(hit.normal.abs_diff_eq(-Vec3A::Z, 0.0001));
+    assert!(quad.intersection(&along_z(1.1, 0.)).is_empty());
+}
+
+#[test]
+fn disks_are_hit_within_their_radius() {
+    let disk = Disk::new(Vertex::new(0., 0., 5.), -Vec3A::Z, 1., white());
+    assert_hits(summary(&disk, &along_z(0.6, 0.6)), &[(5., true)]);
+    assert!(disk.intersection(&along_z(0.8, 0.8)).is_empty());
+}
+
+#[test]
+fn boxes_are_entered_and_left() {
+    let cube = AxisBox::new(Vertex::new(-1., -1., 4.), Vertex::new(1., 1., 6.), white());
+    assert_hits(
+        summary(&cube, &along_z(0.5, 0.)),
+        &[(4., true), (6., false)],
+    );
+    let entry = cube.intersection(&along_z(0.5, 0.)).remove(0);
+    assert!(entry.normal.abs_diff_eq(-Vec3A::Z, 0.0001));
+    assert!(cube.intersection(&along_z(1.5, 0.)).is_empty());
+}
+
+#[test]
+fn cylinders_have_sides_and_caps() {
+    let cylinder = Cylinder::new(Vertex::new(0., 0., 4.), Vec3A::new(0., 0., 2.), 1., white());
+    // through the caps, then through the curved side
+    assert_hits(
+        summary(&cylinder, &along_z(0.5, 0.)),
+        &[(4., true), (6., false)],
+    );
+    assert_hits(
+        summary(&cylinder, &along_x(0., 5.)),
+        &[(4., true), (6., false)],
+    );
+    // past the end of the side
+    assert!(cylinder.intersection(&along_x(0., 6.5)).is_empty());
+}
+
+#[test]
+fn cones_narrow_to_their_apex() {
+    // halfway up the radius is a half
+    let cone = Cone::new(
+        Vertex::new(0., -1., 5.),
+        Vec3A::new(0., 2., 0.),
+        1.,
+        white(),
+    );
+    assert_hits(
+        summary(&cone, &along_x(0., 5.)),
+        &[(4.5, true), (5.5, false)],
+    );
+    assert!(cone.intersection(&along_x(0.9, 5.5)).is_empty());
+    // up through the base cap and out of the side
+    let up = Ray::new(Vertex::new(0.25, -5., 5.), Vec3A::Y);
+    assert_hits(summary(&cone, &up), &[(4., true), (5.5, false)]);
+}
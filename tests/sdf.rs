@@ -0,0 +1,82 @@
+use glam::{Affine3A, Vec3, Vec3A};
+use raytracer::{
+    aabb::Aabb,
+    colour::Colour,
+    material::diffuse::Diffuse,
+    object::{
+        sdf::{
+            operators::{Bounded, Repeat, Union},
+            shapes::{Ball, Cuboid},
+            Sdf,
+        },
+        Object,
+    },
+    ray::Ray,
+    Vertex,
+};
+
+fn white() -> Diffuse {
+    Diffuse::new(Colour::from_rgb(1., 1., 1.))
+}
+
+fn hit_distances(object: &dyn Object, ray: &Ray) -> Vec<f32> {
+    object.intersection(ray).iter().map(|h| h.t).collect()
+}
+
+#[test]
+fn balls_are_hit_where_spheres_are() {
+    let mut ball = Sdf::new(Ball { radius: 1. }, white());
+    ball.apply_transform(Affine3A::from_translation(Vec3::new(0., 0., 5.)));
+    let t = hit_distances(&ball, &Ray::new(Vertex::ZERO, Vec3A::Z));
+    assert_eq!(t.len(), 2, "{t:?}");
+    assert!(
+        (t[0] - 4.).abs() < 0.001 && (t[1] - 6.).abs() < 0.001,
+        "{t:?}"
+    );
+    assert!(hit_distances(&ball, &Ray::new(Vertex::new(0., 2., 0.), Vec3A::Z)).is_empty());
+}
+
+#[test]
+fn bounds_follow_the_shapes() {
+    let mut shape = Sdf::new(
+        Union(
+            Ball { radius: 1. },
+            Cuboid {
+                half_size: Vec3A::new(1., 2., 2.),
+            },
+        ),
+        white(),
+    );
+    shape.apply_transform(Affine3A::from_translation(Vec3::new(0., 0., 5.)));
+    assert_eq!(
+        shape.bounds(),
+        Some(Aabb::new(
+            Vertex::new(-3., -3., 2.),
+            Vertex::new(3., 3., 8.)
+        ))
+    );
+
+    // repeated copies fill space, so there's nothing to bound
+    let repeat = Repeat {
+        inner: Ball { radius: 1. },
+        period: Vec3A::new(4., 0., 0.),
+    };
+    assert_eq!(Sdf::new(repeat, white()).bounds(), None);
+}
+
+#[test]
+fn closures_can_be_given_a_radius() {
+    let field = |p: Vertex| p.length() - 1.;
+    assert_eq!(Sdf::new(field, white()).bounds(), None);
+
+    let bounded = Sdf::new(
+        Bounded {
+            inner: field,
+            radius: 1.,
+        },
+        white(),
+    );
+    assert!(bounded.bounds().is_some());
+    let t = hit_distances(&bounded, &Ray::new(Vertex::new(0., 0., -5.), Vec3A::Z));
+    assert!((t[0] - 4.).abs() < 0.001, "{t:?}");
+}
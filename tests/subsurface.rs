@@ -0,0 +1,60 @@
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    light::{point::Point, Light},
+    material::subsurface::Subsurface,
+    object::{plane::Plane, sphere::Sphere},
+    photonmap::PhotonMap,
+    ray::Ray,
+    scene::Scene,
+    Vertex,
+};
+
+fn wax() -> Subsurface {
+    // an albedo of 0.8 in every channel
+    let c = |v: f32| Colour::from_rgb(v, v, v);
+    Subsurface::new(c(0.8), c(0.2), 0.1)
+}
+
+fn lit_from(scene: &mut Scene, light: &Point) -> Colour {
+    scene.add_light(light.clone());
+    let empty = Scene::default();
+    let pmap = PhotonMap::build(&empty);
+    let viewer = Vertex::new(0., 1., -1.);
+    let mut sum = Colour::default();
+    for _ in 0..200 {
+        sum += scene
+            .raytrace(Ray::new(viewer, -viewer), 4, viewer, &pmap)
+            .0
+            / 200.;
+    }
+    sum
+}
+
+#[test]
+fn open_surfaces_scatter_diffusely() {
+    // a plane has no inside to walk through, so it shades like a diffuse floor
+    let light = Point::new(Vertex::new(1., 2., 0.), Colour::from_rgb(1., 1., 1.));
+    let mut scene = Scene::default();
+    scene.add_object(Plane::new(Vec3A::Y, Vertex::ZERO, wax()));
+    let colour = lit_from(&mut scene, &light);
+
+    let (ldir, _) = light.get_direction(Vertex::ZERO);
+    let expected = light.get_intensity(Vertex::ZERO).r * 0.8 * ldir.y;
+    assert!(
+        (colour.r - expected).abs() < 0.0001,
+        "{} vs {}",
+        colour.r,
+        expected
+    );
+}
+
+#[test]
+fn closed_solids_are_walked_through() {
+    // light leaves a ball near where it came in, so a ball lit from the front still shows it
+    let light = Point::new(Vertex::new(0., 3., -3.), Colour::from_rgb(1., 1., 1.));
+    let mut scene = Scene::default();
+    scene.add_object(Sphere::new(Vertex::ZERO, 0.5, wax()));
+    let colour = lit_from(&mut scene, &light);
+    assert!(colour.r > 0.01, "{}", colour.r);
+}
@@ -0,0 +1,118 @@
+use acap::Coordinates;
+use glam::Vec3A;
+use raytracer::{
+    colour::Colour,
+    material::{dielectric::Dielectric, diffuse::Diffuse, Material},
+    object::{plane::Plane, triangle::Triangle, Object},
+    photonmap::{Interaction, Photon, PhotonMap, Type},
+    ray::Ray,
+    scene::Scene,
+    Vertex,
+};
+
+fn white() -> Diffuse {
+    Diffuse::new(Colour::from_rgb(1., 1., 1.))
+}
+
+// a triangle across the z = depth plane, wound to face back along -z or forward along +z
+fn facing_back<M>(depth: f32, material: M) -> Triangle
+where
+    M: Material + Send + Sync + 'static,
+{
+    Triangle::new(
+        [
+            Vertex::new(-2., -2., depth),
+            Vertex::new(0., 2., depth),
+            Vertex::new(2., -2., depth),
+        ],
+        material,
+    )
+}
+
+fn facing_forward<M>(depth: f32, material: M) -> Triangle
+where
+    M: Material + Send + Sync + 'static,
+{
+    Triangle::new(
+        [
+            Vertex::new(-2., -2., depth),
+            Vertex::new(2., -2., depth),
+            Vertex::new(0., 2., depth),
+        ],
+        material,
+    )
+}
+
+#[test]
+fn triangles_enter_through_their_front() {
+    let ray = Ray::new(Vertex::new(0., 0., 0.), Vec3A::Z);
+
+    let front = facing_back(5., white());
+    let hits = front.intersection(&ray);
+    assert!(hits[0].entering);
+    assert_eq!(hits[0].normal, -Vec3A::Z);
+
+    // hitting the back leaves the solid, with the normal still turned towards the ray
+    let back = facing_forward(5., white());
+    let hits = back.intersection(&ray);
+    assert!(!hits[0].entering);
+    assert_eq!(hits[0].normal, -Vec3A::Z);
+}
+
+#[test]
+fn plane_normals_face_the_ray() {
+    let plane = Plane::new(Vec3A::Y, Vertex::new(0., 0., 5.), white());
+
+    let hits = plane.intersection(&Ray::new(Vertex::new(0., 5., 5.), -Vec3A::Y));
+    assert!(hits[0].entering);
+    assert_eq!(hits[0].normal, Vec3A::Y);
+
+    let hits = plane.intersection(&Ray::new(Vertex::new(0., -5., 5.), Vec3A::Y));
+    assert!(!hits[0].entering);
+    assert_eq!(hits[0].normal, -Vec3A::Y);
+}
+
+// sine of the angle between a refracted ray and the z axis, retrying past fresnel reflections
+fn refracted_sine(triangle: &Triangle, ray: &Ray) -> f32 {
+    let hit = triangle.intersection(ray).remove(0);
+    for _ in 0..1000 {
+        if let Interaction::Transmitted { ray, .. } = hit.material.interact(&hit) {
+            return ray.direction.normalize().cross(Vec3A::Z).length();
+        }
+    }
+    panic!("ray was never transmitted");
+}
+
+#[test]
+fn dielectric_triangles_refract_by_side() {
+    // thirty degrees from the normal
+    let ray = Ray::new(
+        Vertex::new(-2.5, 0., 0.),
+        Vec3A::new(0.5, 0., 0.75_f32.sqrt()),
+    );
+    let glass = || Dielectric::new(1.5, Colour::from_rgb(1., 1., 1.));
+
+    // going into the glass bends towards the normal
+    let sine = refracted_sine(&facing_back(5., glass()), &ray);
+    assert!((sine - 0.5 * 1.0003 / 1.5).abs() < 0.001, "{sine}");
+
+    // coming out of it bends away
+    let sine = refracted_sine(&facing_forward(5., glass()), &ray);
+    assert!((sine - 0.5 * 1.5 / 1.0003).abs() < 0.001, "{sine}");
+}
+
+#[test]
+fn shadow_photons_pass_out_of_surfaces_they_leave() {
+    // the photon leaves through the first triangle and lands on the front of the second
+    let mut scene = Scene::default();
+    scene.add_object(facing_forward(3., white()));
+    scene.add_object(facing_back(6., white()));
+
+    let photon = Photon {
+        ray: Ray::new(Vertex::new(0., 0., 0.), Vec3A::Z),
+        colour: Colour::from_rgb(0., 0., 0.),
+        type_: Type::Shadow,
+    };
+    let landed = PhotonMap::shadow_photon_trace(&scene, photon).expect("photon was lost");
+    assert!((landed.coord(2) - 6.).abs() < 0.001);
+}